@@ -32,9 +32,8 @@ impl ParseMany for Arg {
         let mut args = Vec::new();
 
         for arg in pair.into_inner() {
-            match arg.as_rule() {
-                Rule::define_argument => args.push(Self::parse(arg)?),
-                _ => {}
+            if arg.as_rule() == Rule::define_argument {
+                args.push(Self::parse(arg)?);
             }
         }
 
@@ -50,6 +49,13 @@ impl Arg {
     pub fn rewrite_many(args: Vec<Arg>, sep: &'static str) -> String {
         args.iter().map(|n| n.rewrite()).join(sep)
     }
+
+    /// Declare the arguments in the current scope
+    pub fn declare_many(args: &[Arg]) {
+        for arg in args {
+            rewrite::scope::declare(&arg.ident, arg.ty.clone());
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -62,9 +68,8 @@ impl Parse for CallArg {
         let mut expr = None;
 
         for call_arg_part in pair.into_inner() {
-            match call_arg_part.as_rule() {
-                Rule::expr => expr = Some(Expression::parse(call_arg_part)?),
-                _ => {}
+            if call_arg_part.as_rule() == Rule::expr {
+                expr = Some(Expression::parse(call_arg_part)?);
             }
         }
 
@@ -78,9 +83,8 @@ impl ParseMany for CallArg {
         let mut args = Vec::new();
 
         for arg in pair.into_inner() {
-            match arg.as_rule() {
-                Rule::call_argument => args.push(Self::parse(arg)?),
-                _ => {}
+            if arg.as_rule() == Rule::call_argument {
+                args.push(Self::parse(arg)?);
             }
         }
 
@@ -97,3 +101,37 @@ impl CallArg {
         args.iter().map(|n| n.rewrite()).join(sep)
     }
 }
+
+/// A closure argument, its type can be left out where the closure's type is known
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClosureParam {
+    pub ident: String,
+    pub ty: Option<Type>,
+}
+
+impl Parse for ClosureParam {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut ident = None;
+        let mut ty = None;
+
+        for arg_part in pair.into_inner() {
+            match arg_part.as_rule() {
+                Rule::ident => ident = Some(arg_part.as_str().to_owned()),
+                Rule::ty => ty = Some(Type::parse(arg_part)?),
+                _ => {}
+            }
+        }
+
+        Some(Self { ident: ident?, ty })
+    }
+}
+
+impl ParseMany for ClosureParam {
+    /// parse from the closure_args rule
+    fn parse_many(pair: Pair<'_, Rule>) -> Option<Vec<Self>> {
+        pair.into_inner()
+            .filter(|n| n.as_rule() == Rule::closure_arg)
+            .map(Self::parse)
+            .collect()
+    }
+}
@@ -3,6 +3,7 @@ use super::*;
 #[derive(Clone, Debug, PartialEq)]
 pub enum BlockPart {
     Var(Variable),
+    Destructure(Destructure),
     Expr(Expression),
     Stmt(Statement),
     BreakKwd,
@@ -20,6 +21,7 @@ impl Parse for BlockPart {
 
         match inner.as_rule() {
             Rule::var => Some(BlockPart::Var(Variable::parse(inner)?)),
+            Rule::let_pattern => Some(BlockPart::Destructure(Destructure::parse(inner)?)),
             Rule::expr => Some(BlockPart::Expr(Expression::parse(inner)?)),
             Rule::stmt => Some(BlockPart::Stmt(Statement::parse(inner)?)),
             Rule::break_kwd => Some(BlockPart::BreakKwd),
@@ -28,9 +30,8 @@ impl Parse for BlockPart {
                 let mut expr = None;
 
                 for rule in inner.into_inner() {
-                    match rule.as_rule() {
-                        Rule::expr => expr = Some(Expression::parse(rule)?),
-                        _ => {}
+                    if rule.as_rule() == Rule::expr {
+                        expr = Some(Expression::parse(rule)?);
                     }
                 }
 
@@ -50,9 +51,8 @@ impl ParseMany for BlockPart {
         let mut blocks = vec![];
 
         for rule in pair.into_inner() {
-            match rule.as_rule() {
-                Rule::in_block => blocks.push(BlockPart::parse(rule)?),
-                _ => {}
+            if rule.as_rule() == Rule::in_block {
+                blocks.push(BlockPart::parse(rule)?);
             }
         }
 
@@ -64,13 +64,17 @@ impl BlockPart {
     pub fn rewrite(&self) -> String {
         match self {
             BlockPart::Var(var) => format!("{};", var.rewrite()),
+            BlockPart::Destructure(destructure) => destructure.rewrite(),
             BlockPart::Expr(expr) => format!("{};", expr.rewrite()),
             BlockPart::Stmt(stmt) => format!("{};", stmt.rewrite()),
             BlockPart::BreakKwd => "break;".to_string(),
             BlockPart::ContinueKwd => "continue;".to_string(),
             BlockPart::Return(expr) => {
                 if let Some(expr) = expr {
-                    format!("return {};", expr.rewrite())
+                    format!(
+                        "return {};",
+                        expr.rewrite_as(rewrite::scope::returns().as_ref())
+                    )
                 } else {
                     "return;".to_string()
                 }
@@ -83,6 +87,99 @@ impl BlockPart {
     }
 
     pub fn rewrite_many(all: Vec<Self>, sep: &'static str) -> String {
-        all.iter().map(|n| n.rewrite()).join(sep)
+        rewrite::scope::push();
+
+        let rewritten = all
+            .iter()
+            .enumerate()
+            .map(|(idx, n)| {
+                rewrite::with_hoisted(|| match n {
+                    BlockPart::Var(var) => format!("{};", var.rewrite_in_block(&all[idx + 1..])),
+                    _ => n.rewrite(),
+                })
+            })
+            .join(sep);
+
+        rewrite::scope::pop();
+
+        rewritten
+    }
+}
+
+/// A block whose last expression is its value, e.g. `{ let b = a * 2; b + 1 }`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueBlock {
+    pub body: Vec<BlockPart>,
+    pub value: Expression,
+}
+
+impl Parse for ValueBlock {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        match pair.as_rule() {
+            // a lone expression, e.g. a match arm
+            Rule::expr => Some(ValueBlock {
+                body: vec![],
+                value: Expression::parse(pair)?,
+            }),
+            _ => {
+                let mut body = vec![];
+                let mut value = None;
+
+                for rule in pair.into_inner() {
+                    match rule.as_rule() {
+                        Rule::in_block => body.push(BlockPart::parse(rule)?),
+                        Rule::expr => value = Some(Expression::parse(rule)?),
+                        _ => {}
+                    }
+                }
+
+                Some(ValueBlock {
+                    body,
+                    value: value?,
+                })
+            }
+        }
+    }
+}
+
+impl ValueBlock {
+    fn declare_lets(&self) {
+        for part in self.body.iter() {
+            if let BlockPart::Var(var) = part {
+                if let Some(ty) = var.ty.clone().or_else(|| var.expr.infer_type()) {
+                    rewrite::scope::declare(&var.ident, ty);
+                }
+            }
+        }
+    }
+
+    /// The type of the value, which can read the `let`s of the body
+    pub fn infer_type(&self) -> Option<Type> {
+        rewrite::scope::push();
+        self.declare_lets();
+
+        let ty = self.value.infer_type();
+
+        rewrite::scope::pop();
+
+        ty
+    }
+
+    /// The body, then `target = value;`
+    pub fn rewrite_assign(&self, target: &str) -> String {
+        rewrite::scope::push();
+
+        let mut rewritten = BlockPart::rewrite_many(self.body.clone(), "\n");
+
+        // `let`s in the body stay visible to the value
+        self.declare_lets();
+
+        rewritten.push_str(&rewrite::with_hoisted(|| {
+            format!("\n{} = {};", target, self.value.rewrite())
+        }));
+
+        rewrite::scope::pop();
+
+        rewritten
     }
 }
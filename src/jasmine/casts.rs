@@ -0,0 +1,124 @@
+use super::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cast {
+    pub expr: Box<Expression>,
+    pub ty: Type,
+}
+
+impl Parse for Cast {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut expr = None;
+        let mut cast = None;
+
+        for rule in pair.into_inner() {
+            match rule.as_rule() {
+                Rule::expr => expr = Some(Expression::parse(rule)?),
+                Rule::base_expr => expr = Some(Expression::Base(BaseExpr::parse(rule)?)),
+                Rule::definition => expr = Some(Expression::Definition(Definition::parse(rule)?)),
+                Rule::ty => {
+                    // `x as A as B` is `(x as A) as B`
+                    let inner = match cast {
+                        Some(cast) => Expression::Cast(cast),
+                        None => expr.take()?,
+                    };
+
+                    cast = Some(Cast {
+                        expr: Box::new(inner),
+                        ty: Type::parse(rule)?,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        cast
+    }
+}
+
+impl Cast {
+    pub fn infer_type(&self) -> Option<Type> {
+        Some(self.ty.clone())
+    }
+
+    /// Whether `source as target` converts between numbers, chars, bools and enums without data
+    fn is_valid(source: &Type, target: &Type) -> bool {
+        let is_number = |ty: &Type| ty.numeric_rank().is_some();
+
+        match &source.which {
+            _ if is_number(source) || source.which == WhichType::Char => {
+                is_number(target) || target.which == WhichType::Char
+            }
+            WhichType::Bool => target.is_integer() || target.which == WhichType::Bool,
+            WhichType::Ident(ident) => {
+                target.is_integer()
+                    && rewrite::scope::find_enum(ident)
+                        .is_some_and(|e| e.variants.iter().all(|v| v.data.is_none()))
+            }
+            _ => false,
+        }
+    }
+
+    pub fn rewrite(&self) -> String {
+        let source = self.expr.infer_type();
+
+        if self.ty.rewrite_primitive().is_none()
+            || source
+                .as_ref()
+                .is_some_and(|source| !Self::is_valid(source, &self.ty))
+        {
+            rewrite::report(format!(
+                "Cannot cast {} as `{}`, `as` only converts between numbers, chars, bools and enums without data",
+                source
+                    .as_ref()
+                    .map(|ty| format!("`{}`", ty.describe()))
+                    .unwrap_or("a value".to_string()),
+                self.ty.describe()
+            ));
+        }
+
+        let expr = match self.expr.as_ref() {
+            // a java cast binds tighter than any binary operator
+            Expression::Full(full) => format!("({})", full.rewrite()),
+            expr => expr.rewrite(),
+        };
+
+        let Some(target) = self.ty.rewrite_primitive() else {
+            // casting to a non-primitive type does not convert anything
+            return format!("(({}) {})", self.ty.rewrite(), expr);
+        };
+
+        let Some(source) = source else {
+            // the conversion has to be decided at runtime
+            return match self.ty.which {
+                WhichType::Int => format!("_asInt_({})", expr),
+                WhichType::I64 => format!("_asLong_({})", expr),
+                WhichType::I8 | WhichType::I16 => format!("(({}) _asLong_({}))", target, expr),
+                WhichType::Float => format!("_asFloat_({})", expr),
+                WhichType::F32 => format!("(({}) _asFloat_({}))", target, expr),
+                WhichType::Char => format!("_asChar_({})", expr),
+                _ => format!("(({}) {})", target, expr),
+            };
+        };
+
+        let as_int = match &source.which {
+            WhichType::Bool if self.ty.is_integer() => format!("(({}) ? 1 : 0)", expr),
+            WhichType::Ident(ident)
+                if self.ty.is_integer() && rewrite::scope::find_enum(ident).is_some() =>
+            {
+                format!("(({}).currentVariant - 1)", expr)
+            }
+            _ => {
+                return match source.rewrite_primitive() {
+                    Some(source) => format!("(({}) ({}) {})", target, source, expr),
+                    None => format!("(({}) {})", target, expr),
+                };
+            }
+        };
+
+        match self.ty.which {
+            WhichType::Int => as_int,
+            _ => format!("(({}) {})", target, as_int),
+        }
+    }
+}
@@ -50,13 +50,23 @@ impl IfLetExpression {
         )
     }
 
+    /// The java expression reading the bound data
+    pub fn rewrite_source(&self) -> String {
+        format!(
+            "({})._getData_{}()",
+            self.expr.rewrite(),
+            self.variant_ident
+        )
+    }
+
     pub fn rewrite_data(&self) -> String {
+        rewrite::scope::declare(&self.data_ident, self.data_ty.clone());
+
         format!(
-            "{} {} = ({})._getData_{}();",
+            "{} {} = {};",
             self.data_ty.rewrite(),
             rewrite_ident(&self.data_ident),
-            self.expr.rewrite(),
-            self.variant_ident
+            self.rewrite_source()
         )
     }
 }
@@ -86,6 +96,24 @@ impl IfExpression {
             IfExpression::IfLet(if_let) => if_let.rewrite(),
         }
     }
+
+    /// A branch of `rewrite::rewrite_if_chain` running `body` if this holds
+    pub fn rewrite_branch(&self, body: &[BlockPart]) -> (Vec<String>, String, String) {
+        let (hoisted, cond) = rewrite::collect_hoisted(|| self.rewrite());
+
+        rewrite::scope::push();
+
+        let data_block = match self {
+            IfExpression::IfLet(if_let) => format!("{}\n", if_let.rewrite_data()),
+            IfExpression::Expr(_) => "".to_string(),
+        };
+
+        let body = data_block + &BlockPart::rewrite_many(body.to_vec(), "\n");
+
+        rewrite::scope::pop();
+
+        (hoisted, cond, body)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -111,29 +139,6 @@ impl Parse for ElifStmt {
     }
 }
 
-impl ElifStmt {
-    pub fn rewrite(&self) -> String {
-        let data_block = {
-            if let IfExpression::IfLet(iflet) = &self.cond {
-                format!("{}\n", iflet.rewrite_data())
-            } else {
-                "".to_string()
-            }
-        };
-
-        format!(
-            " else if ({}) {{\n{}{}\n}}",
-            self.cond.rewrite(),
-            data_block,
-            BlockPart::rewrite_many(self.body.clone(), "\n")
-        )
-    }
-
-    pub fn rewrite_many(all: Vec<Self>, sep: &'static str) -> String {
-        all.iter().map(|n| n.rewrite()).join(sep)
-    }
-}
-
 #[derive(Clone, Debug, PartialEq)]
 pub struct IfStatement {
     pub cond: IfExpression,
@@ -156,9 +161,8 @@ impl Parse for IfStatement {
                 Rule::else_if_def => else_ifs.push(ElifStmt::parse(rule)?),
                 Rule::else_def => {
                     for rule in rule.into_inner() {
-                        match rule.as_rule() {
-                            Rule::block => else_body = Some(BlockPart::parse_many(rule)?),
-                            _ => {}
+                        if rule.as_rule() == Rule::block {
+                            else_body = Some(BlockPart::parse_many(rule)?);
                         }
                     }
                 }
@@ -177,28 +181,206 @@ impl Parse for IfStatement {
 
 impl IfStatement {
     pub fn rewrite(&self) -> String {
-        let data_block = {
-            if let IfExpression::IfLet(iflet) = &self.cond {
-                format!("{}\n", iflet.rewrite_data())
-            } else {
-                "".to_string()
+        let branches = std::iter::once(self.cond.rewrite_branch(&self.body))
+            .chain(
+                self.else_ifs
+                    .iter()
+                    .map(|elif| elif.cond.rewrite_branch(&elif.body)),
+            )
+            .collect_vec();
+
+        rewrite::rewrite_if_chain(
+            branches,
+            self.else_body
+                .as_ref()
+                .map(|body| BlockPart::rewrite_many(body.clone(), "\n")),
+        )
+    }
+}
+
+/// `if a > b { a } else { b }` used as a value
+#[derive(Clone, Debug, PartialEq)]
+pub struct IfValue {
+    /// the `if` and each `else if`
+    pub branches: Vec<(IfExpression, ValueBlock)>,
+    pub else_block: ValueBlock,
+}
+
+impl Parse for IfValue {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut conds = vec![];
+        let mut blocks = vec![];
+
+        for rule in pair.into_inner() {
+            match rule.as_rule() {
+                Rule::if_expr => conds.push(IfExpression::parse(rule)?),
+                Rule::value_block => blocks.push(ValueBlock::parse(rule)?),
+                _ => {}
             }
+        }
+
+        let else_block = blocks.pop()?;
+
+        Some(IfValue {
+            branches: conds.into_iter().zip(blocks).collect(),
+            else_block,
+        })
+    }
+}
+
+impl IfValue {
+    pub fn infer_type(&self) -> Option<Type> {
+        self.branches
+            .iter()
+            .map(|(cond, block)| {
+                rewrite::scope::push();
+
+                if let IfExpression::IfLet(if_let) = cond {
+                    rewrite::scope::declare(&if_let.data_ident, if_let.data_ty.clone());
+                }
+
+                let ty = block.infer_type();
+
+                rewrite::scope::pop();
+
+                ty
+            })
+            .chain(std::iter::once(self.else_block.infer_type()))
+            .find_map(|ty| ty)
+    }
+
+    /// A ternary if every branch is a lone expression,
+    /// otherwise a temporary assigned in each branch
+    pub fn rewrite(&self, ty: Option<&Type>) -> String {
+        let is_ternary = self.else_block.body.is_empty()
+            && self.branches.iter().all(|(_, block)| block.body.is_empty());
+
+        if is_ternary {
+            return self.rewrite_ternary(ty);
+        }
+
+        let Some((ty, target)) = self.declare_target(ty) else {
+            return "null".to_string();
         };
 
-        format!(
-            "if ({}) {{\n{}{}\n}}{}{}",
-            self.cond.rewrite(),
-            data_block,
-            BlockPart::rewrite_many(self.body.clone(), "\n"),
-            ElifStmt::rewrite_many(self.else_ifs.clone(), "\n"),
-            if let Some(else_body) = &self.else_body {
-                format!(
-                    " else {{\n{}\n}}",
-                    BlockPart::rewrite_many(else_body.clone(), "\n")
-                )
-            } else {
-                "".to_string()
-            },
-        )
+        let branches = self
+            .branches
+            .iter()
+            .map(|(cond, block)| {
+                let (hoisted, cond_rewritten) = rewrite::collect_hoisted(|| cond.rewrite());
+
+                rewrite::scope::push();
+
+                let data_block = match cond {
+                    IfExpression::IfLet(if_let) => format!("{}\n", if_let.rewrite_data()),
+                    IfExpression::Expr(_) => "".to_string(),
+                };
+
+                let body = data_block + &block.rewrite_assign(&target);
+
+                rewrite::scope::pop();
+
+                (hoisted, cond_rewritten, body)
+            })
+            .collect_vec();
+
+        let else_body = self.else_block.rewrite_assign(&target);
+
+        rewrite::hoist(format!(
+            "{} {};\n{}",
+            ty.rewrite(),
+            target,
+            rewrite::rewrite_if_chain(branches, Some(else_body))
+        ));
+
+        target
+    }
+
+    /// The branches as nested ternaries. Statements hoisted out of a value, or out of any
+    /// condition but the first, must only run when their branch is reached,
+    /// so then the branches assign a temporary instead.
+    fn rewrite_ternary(&self, ty: Option<&Type>) -> String {
+        let mut branches = vec![];
+
+        for (cond, block) in self.branches.iter() {
+            let (hoisted, cond_rewritten) = rewrite::collect_hoisted(|| cond.rewrite());
+
+            let value = match cond {
+                IfExpression::IfLet(if_let) => {
+                    rewrite::scope::push();
+                    rewrite::scope::declare(&if_let.data_ident, if_let.data_ty.clone());
+
+                    let aliases = [(if_let.data_ident.clone(), if_let.rewrite_source())];
+                    let value = rewrite::scope::with_aliases(&aliases, || {
+                        rewrite::collect_hoisted(|| block.value.rewrite())
+                    });
+
+                    rewrite::scope::pop();
+
+                    value
+                }
+                IfExpression::Expr(_) => rewrite::collect_hoisted(|| block.value.rewrite()),
+            };
+
+            branches.push((hoisted, cond_rewritten, value));
+        }
+
+        let else_value = rewrite::collect_hoisted(|| self.else_block.value.rewrite());
+
+        let is_lazy = !else_value.0.is_empty()
+            || branches
+                .iter()
+                .enumerate()
+                .any(|(idx, (hoisted, _, value))| {
+                    (idx > 0 && !hoisted.is_empty()) || !value.0.is_empty()
+                });
+
+        if !is_lazy {
+            for (hoisted, _, _) in branches.iter_mut() {
+                hoisted.drain(..).for_each(rewrite::hoist);
+            }
+
+            return branches
+                .into_iter()
+                .rev()
+                .fold(else_value.1, |rest, (_, cond, (_, value))| {
+                    format!("({} ? {} : {})", cond, value, rest)
+                });
+        }
+
+        let Some((ty, target)) = self.declare_target(ty) else {
+            return "null".to_string();
+        };
+
+        let assign = |(hoisted, value): (Vec<String>, String)| {
+            hoisted
+                .into_iter()
+                .chain(std::iter::once(format!("{} = {};", target, value)))
+                .join("\n")
+        };
+
+        let branches = branches
+            .into_iter()
+            .map(|(hoisted, cond, value)| (hoisted, cond, assign(value)))
+            .collect_vec();
+
+        rewrite::hoist(format!(
+            "{} {};\n{}",
+            ty.rewrite(),
+            target,
+            rewrite::rewrite_if_chain(branches, Some(assign(else_value)))
+        ));
+
+        target
+    }
+
+    /// The type of the value and a fresh temporary to assign it to
+    fn declare_target(&self, ty: Option<&Type>) -> Option<(Type, String)> {
+        let Some(ty) = ty.cloned().or_else(|| self.infer_type()) else {
+            rewrite::report("Cannot infer the type of this `if` expression, add a type annotation");
+            return None;
+        };
+
+        Some((ty, rewrite::temp_ident()))
     }
 }
@@ -0,0 +1,90 @@
+use super::*;
+
+/// `const IDENT: ty = expr;`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constant {
+    pub ident: String,
+    pub visibility: Visibility,
+    pub ty: Type,
+    pub expr: Expression,
+}
+
+impl Parse for Constant {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut ident = None;
+        let mut visibility = Visibility::Private;
+        let mut ty = None;
+        let mut expr = None;
+
+        for rule in pair.into_inner() {
+            match rule.as_rule() {
+                Rule::ident => ident = Some(rule.as_str().to_string()),
+                Rule::visibility => visibility = Visibility::parse(rule)?,
+                Rule::ty => ty = Some(Type::parse(rule)?),
+                Rule::expr => expr = Some(Expression::parse(rule)?),
+                _ => {}
+            }
+        }
+
+        Some(Constant {
+            ident: ident?,
+            visibility,
+            ty: ty?,
+            expr: expr?,
+        })
+    }
+}
+
+impl Constant {
+    pub fn rewrite(&self) -> String {
+        if !self.expr.is_constant() {
+            rewrite::report(format!(
+                "The value of `const {}` must be a compile-time constant",
+                self.ident
+            ));
+        }
+
+        format!(
+            "{}static final {} {} = {};",
+            self.visibility.rewrite(),
+            self.ty.rewrite(),
+            rewrite_ident(&self.ident),
+            self.expr.rewrite_as(Some(&self.ty))
+        )
+    }
+}
+
+/// `static IDENT: ty = expr;` or `static mut IDENT: ty = expr;`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Static {
+    pub visibility: Visibility,
+    pub var: Variable,
+}
+
+impl Parse for Static {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let visibility = match pair
+            .clone()
+            .into_inner()
+            .find(|n| n.as_rule() == Rule::visibility)
+        {
+            Some(rule) => Visibility::parse(rule)?,
+            None => Visibility::Private,
+        };
+
+        Some(Static {
+            visibility,
+            var: Variable::parse(pair)?,
+        })
+    }
+}
+
+impl Static {
+    pub fn rewrite(&self) -> String {
+        format!(
+            "{}static {};",
+            self.visibility.rewrite(),
+            self.var.rewrite()
+        )
+    }
+}
@@ -4,7 +4,8 @@ use super::*;
 pub struct Variable {
     pub mutable: bool,
     pub ident: String,
-    pub ty: Type,
+    /// `None` if the type should be inferred from `expr`
+    pub ty: Option<Type>,
     pub expr: Expression,
 }
 
@@ -28,27 +29,205 @@ impl Parse for Variable {
         Some(Variable {
             mutable,
             ident: ident?,
-            ty: ty?,
+            ty,
             expr: expr?,
         })
     }
 }
 
+/// `let s = Stack::new();`, a static call on a generic type whose type arguments java would
+/// infer as `Object`
+struct GenericStaticCall<'a> {
+    ty_ident: String,
+    call: &'a FunctionCall,
+    /// the type parameters of `ty_ident`
+    params: Vec<String>,
+    /// the parameter types of the call, in terms of `params`
+    arg_types: Vec<Type>,
+    /// the type of the call, in terms of `params`
+    returns: Type,
+}
+
 impl Variable {
+    fn generic_static_call(&self) -> Option<GenericStaticCall<'_>> {
+        let Expression::Base(base) = &self.expr else {
+            return None;
+        };
+
+        let BaseExprType::Ident {
+            data,
+            static_fn: Some(call),
+            after_dot: None,
+            ..
+        } = base.kind.as_ref()
+        else {
+            return None;
+        };
+
+        let ty_ident = rewrite::scope::resolve_self(data);
+        let variant = rewrite::scope::find_enum(&ty_ident)
+            .and_then(|e| e.variants.into_iter().find(|v| v.ident == call.ident));
+
+        let (params, arg_types, returns) = match (ty_ident.as_str(), variant) {
+            ("Vec", _) if call.ident == "new" => (
+                vec!["T".to_string()],
+                vec![],
+                Type::new(WhichType::Array {
+                    ty: Box::new(Type::new(WhichType::Ident("T".to_string()))),
+                    dimensions: 1,
+                }),
+            ),
+            (_, Some(variant)) => {
+                let generics = rewrite::scope::find_enum(&ty_ident)?.generics;
+
+                (
+                    rewrite::scope::type_params(&ty_ident),
+                    variant.data.into_iter().collect(),
+                    rewrite::self_type(&ty_ident, generics.as_ref()),
+                )
+            }
+            _ => (
+                rewrite::scope::type_params(&ty_ident),
+                rewrite::scope::find_method(&ty_ident, &call.ident)?.param_types(),
+                rewrite::scope::find_method_returns(&ty_ident, &call.ident)??,
+            ),
+        };
+
+        params
+            .iter()
+            .any(|p| returns.mentions(p))
+            .then_some(GenericStaticCall {
+                ty_ident,
+                call,
+                params,
+                arg_types,
+                returns,
+            })
+    }
+
+    /// Infer the type arguments of `let s = Stack::new();` from the arguments of the call and of
+    /// the first method calls like `s.push(...)` in `rest`, where a parameter is a type parameter
+    fn infer_from_usage(&self, rest: &[BlockPart]) -> Option<Type> {
+        let GenericStaticCall {
+            ty_ident,
+            call,
+            params,
+            arg_types,
+            returns,
+        } = self.generic_static_call()?;
+
+        let method_params = |method: &str| match ty_ident.as_str() {
+            "Vec" => match method {
+                "push" => vec![Type::new(WhichType::Ident("T".to_string()))],
+                _ => vec![],
+            },
+            _ => rewrite::scope::find_method(&ty_ident, method)
+                .map(|f| f.param_types())
+                .unwrap_or_default(),
+        };
+
+        let method_calls = rest.iter().filter_map(|part| {
+            let BlockPart::Expr(Expression::Base(base)) = part else {
+                return None;
+            };
+
+            let BaseExprType::Ident {
+                data,
+                static_fn: None,
+                unit_enum: None,
+                after_dot: Some(after_dot),
+            } = base.kind.as_ref()
+            else {
+                return None;
+            };
+
+            let AfterDotExprType::ObjectFnCall {
+                data: call,
+                after_dot: None,
+            } = after_dot.as_ref()
+            else {
+                return None;
+            };
+
+            (data == &self.ident).then(|| (method_params(&call.ident), call))
+        });
+
+        let mut args: Vec<Option<Type>> = vec![None; params.len()];
+
+        for (types, call) in std::iter::once((arg_types, call)).chain(method_calls) {
+            for (ty, arg) in types.iter().zip(call.args.iter()) {
+                let WhichType::Ident(ident) = &ty.which else {
+                    continue;
+                };
+
+                if let Some(idx) = params.iter().position(|p| p == ident) {
+                    args[idx] = args[idx].take().or_else(|| arg.expr.infer_type());
+                }
+            }
+
+            if args.iter().all(|n| n.is_some()) {
+                break;
+            }
+        }
+
+        let args = args.into_iter().collect::<Option<Vec<_>>>()?;
+
+        Some(returns.substitute(&params, &args))
+    }
+
     pub fn rewrite(&self) -> String {
+        self.rewrite_in_block(&[])
+    }
+
+    /// `rest` are the block parts after this variable, used to infer its type
+    pub fn rewrite_in_block(&self, rest: &[BlockPart]) -> String {
+        let ty = self
+            .ty
+            .clone()
+            .or_else(|| self.expr.infer_type())
+            .or_else(|| self.infer_from_usage(rest));
+
+        let is_closure = match &self.expr {
+            Expression::Definition(def) => matches!(def.kind, DefinitionType::Closure(_)),
+            Expression::Base(base) => base.operators.is_empty() && base.kind.is_fn_value(),
+            _ => false,
+        };
+
+        // java would infer `Object` for the type arguments
+        if ty.is_none() && self.generic_static_call().is_some() {
+            rewrite::report(format!(
+                "Cannot infer the type of `{}`, add a type annotation",
+                self.ident
+            ));
+        }
+
+        // a java lambda or method reference can not be stored in a `var`
+        if ty.is_none() && is_closure {
+            rewrite::report(format!(
+                "Cannot infer the type of closure `{}`, add a type annotation",
+                self.ident
+            ));
+        }
+
+        let expr = self.expr.rewrite_as(ty.as_ref());
+
+        if let Some(ty) = &ty {
+            rewrite::scope::declare(&self.ident, ty.clone());
+        }
+
         format!(
             "{} {} {} = {}",
             if !self.mutable { "final" } else { "" },
-            self.ty.rewrite(),
+            ty.map(|ty| ty.rewrite()).unwrap_or("var".to_string()),
             rewrite_ident(&self.ident),
-            self.expr.rewrite()
+            expr
         )
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Range {
-    pub begin: i64,
+    pub begin: Box<Expression>,
     pub end: Box<Expression>,
     pub inclusive: bool,
 }
@@ -61,7 +240,8 @@ impl Parse for Range {
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
-                Rule::int if begin.is_none() => begin = Some(rule.as_str().parse::<i64>().ok()?),
+                Rule::int => begin = Some(Expression::Definition(Definition::parse_kind(rule)?)),
+                Rule::base_expr => begin = Some(Expression::Base(BaseExpr::parse(rule)?)),
                 Rule::expr => end = Some(Box::new(Expression::parse(rule)?)),
                 Rule::range_incl => inclusive = true,
                 _ => {}
@@ -69,7 +249,7 @@ impl Parse for Range {
         }
 
         Some(Self {
-            begin: begin?,
+            begin: Box::new(begin?),
             end: end?,
             inclusive,
         })
@@ -78,23 +258,234 @@ impl Parse for Range {
 
 impl Range {
     pub fn rewrite(&self) -> String {
+        let int = Type::new(WhichType::Int);
+
         format!(
             "new Range({}, {}, {})",
-            self.begin,
-            self.end.rewrite(),
+            self.begin.rewrite_as(Some(&int)),
+            self.end.rewrite_as(Some(&int)),
             if self.inclusive { "true" } else { "false" }
         )
     }
 }
 
+/// `i8`, `i16`, `i32` or `i64` after an integer literal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntSuffix {
+    I8,
+    I16,
+    I32,
+    I64,
+}
+
+impl IntSuffix {
+    fn from_type(ty: &Type) -> Option<Self> {
+        match ty.which {
+            WhichType::I8 => Some(IntSuffix::I8),
+            WhichType::I16 => Some(IntSuffix::I16),
+            WhichType::Int => Some(IntSuffix::I32),
+            WhichType::I64 => Some(IntSuffix::I64),
+            _ => None,
+        }
+    }
+
+    fn ty(&self) -> Type {
+        Type::new(match self {
+            IntSuffix::I8 => WhichType::I8,
+            IntSuffix::I16 => WhichType::I16,
+            IntSuffix::I32 => WhichType::Int,
+            IntSuffix::I64 => WhichType::I64,
+        })
+    }
+
+    fn range(&self) -> (i128, i128) {
+        match self {
+            IntSuffix::I8 => (i8::MIN as i128, i8::MAX as i128),
+            IntSuffix::I16 => (i16::MIN as i128, i16::MAX as i128),
+            IntSuffix::I32 => (i32::MIN as i128, i32::MAX as i128),
+            IntSuffix::I64 => (i64::MIN as i128, i64::MAX as i128),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntLiteral {
+    /// as written, for error messages
+    pub text: String,
+    pub value: i128,
+    /// `None` if unsuffixed, then the literal takes the type it is used as
+    pub suffix: Option<IntSuffix>,
+}
+
+impl Parse for IntLiteral {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let text = pair.as_str().trim().to_string();
+        let mut value = None;
+        let mut suffix = None;
+        let mut sign = 1;
+
+        for rule in pair.into_inner() {
+            let digits = rule.as_str().replace('_', "");
+
+            match rule.as_rule() {
+                Rule::hex_int => value = i128::from_str_radix(&digits[2..], 16).ok(),
+                Rule::bin_int => value = i128::from_str_radix(&digits[2..], 2).ok(),
+                Rule::oct_int => value = i128::from_str_radix(&digits[2..], 8).ok(),
+                Rule::dec_int => value = digits.parse().ok(),
+                Rule::neg_lit => sign = -1,
+                Rule::int_suffix => {
+                    suffix = match rule.as_str() {
+                        "i8" => Some(IntSuffix::I8),
+                        "i16" => Some(IntSuffix::I16),
+                        "i64" => Some(IntSuffix::I64),
+                        _ => Some(IntSuffix::I32),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Some(IntLiteral {
+            text,
+            value: sign * value?,
+            suffix,
+        })
+    }
+}
+
+impl IntLiteral {
+    pub fn infer_type(&self) -> Type {
+        self.suffix.unwrap_or(IntSuffix::I32).ty()
+    }
+
+    pub fn rewrite(&self) -> String {
+        self.rewrite_as(&self.infer_type())
+    }
+
+    /// Rewrite as a literal of the numeric type `ty`
+    pub fn rewrite_as(&self, ty: &Type) -> String {
+        let Some(suffix) = IntSuffix::from_type(ty) else {
+            // floating point, java has no integer to float conversion in literals
+            return FloatLiteral {
+                text: self.text.clone(),
+                value: self.value as f64,
+                is_f32: ty.which == WhichType::F32,
+                suffixed: true,
+            }
+            .rewrite();
+        };
+
+        let (min, max) = suffix.range();
+
+        if self.value < min || self.value > max {
+            rewrite::report(format!(
+                "Literal `{}` is out of range for `{}`",
+                self.text,
+                ty.describe()
+            ));
+        }
+
+        match suffix {
+            IntSuffix::I8 => format!("((byte) {})", self.value),
+            IntSuffix::I16 => format!("((short) {})", self.value),
+            IntSuffix::I32 => self.value.to_string(),
+            IntSuffix::I64 => format!("{}L", self.value),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloatLiteral {
+    /// as written, for error messages
+    pub text: String,
+    pub value: f64,
+    /// `f32` instead of the default `f64`
+    pub is_f32: bool,
+    /// unsuffixed literals take the type they are used as
+    pub suffixed: bool,
+}
+
+impl Parse for FloatLiteral {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let text = pair.as_str().trim().to_string();
+        let mut value = None;
+        let mut is_f32 = false;
+        let mut suffixed = false;
+        let mut sign = 1.0;
+
+        for rule in pair.into_inner() {
+            match rule.as_rule() {
+                Rule::neg_lit => sign = -1.0,
+                Rule::float_digits => {
+                    value = rule
+                        .as_str()
+                        .replace('_', "")
+                        .trim_end_matches('.')
+                        .parse::<f64>()
+                        .ok()
+                }
+                Rule::float_suffix => {
+                    is_f32 = rule.as_str() == "f32";
+                    suffixed = true;
+                }
+                _ => {}
+            }
+        }
+
+        Some(FloatLiteral {
+            text,
+            value: sign * value?,
+            is_f32,
+            suffixed,
+        })
+    }
+}
+
+impl FloatLiteral {
+    pub fn infer_type(&self) -> Type {
+        Type::new(match self.is_f32 {
+            true => WhichType::F32,
+            false => WhichType::Float,
+        })
+    }
+
+    pub fn rewrite(&self) -> String {
+        let max = if self.is_f32 {
+            f32::MAX as f64
+        } else {
+            f64::MAX
+        };
+
+        if !self.value.is_finite() || self.value.abs() > max {
+            rewrite::report(format!(
+                "Literal `{}` is out of range for `{}`",
+                self.text,
+                if self.is_f32 { "f32" } else { "f64" }
+            ));
+        }
+
+        // `{:?}` always keeps a `.` or an exponent, so java reads it as floating point
+        match self.is_f32 {
+            true => format!("{:?}f", self.value),
+            false => format!("{:?}", self.value),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum DefinitionType {
     Bool(bool),
-    Int(i64),
-    Float(f64),
+    Int(IntLiteral),
+    Float(FloatLiteral),
     String(Vec<CharDecl>),
     Char(CharDecl),
     Array(Vec<Expression>),
+    /// `[value; count]`
+    ArrayRepeat {
+        value: Box<Expression>,
+        count: Box<Expression>,
+    },
+    Tuple(Vec<Expression>),
     Struct(CreateStructure),
     Closure(Closure),
     Range(Range),
@@ -107,38 +498,23 @@ pub struct Definition {
 
 impl Parse for Definition {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        let Some(rule) = pair.into_inner().nth(0) else {
-            return None;
-        };
+        Definition::parse_kind(pair.into_inner().next()?)
+    }
+}
 
+impl Definition {
+    /// Parse the rule inside a `definition`, e.g. a lone `int` or `string`
+    pub fn parse_kind(rule: Pair<'_, Rule>) -> Option<Self> {
         let mut kind = None;
 
         match rule.as_rule() {
             Rule::r#struct => kind = Some(DefinitionType::Struct(CreateStructure::parse(rule)?)),
-            Rule::float => {
-                let mut rule_str = rule.as_str();
-
-                if rule_str.ends_with("f") {
-                    rule_str = &rule_str[..rule_str.len() - 1];
-                }
-
-                kind = Some(DefinitionType::Float(rule_str.parse::<f64>().ok()?))
-            }
-            Rule::int => {
-                let mut rule_str = rule.as_str().trim();
-
-                if rule_str.ends_with("i") {
-                    rule_str = &rule_str[..rule_str.len() - 1];
-                }
-
-                kind = Some(DefinitionType::Int(rule_str.parse::<i64>().ok()?))
-            }
+            Rule::float => kind = Some(DefinitionType::Float(FloatLiteral::parse(rule)?)),
+            Rule::int => kind = Some(DefinitionType::Int(IntLiteral::parse(rule)?)),
             Rule::bool => kind = Some(DefinitionType::Bool(rule.as_str().parse::<bool>().ok()?)),
             Rule::string => kind = Some(DefinitionType::String(CharDecl::parse_many(rule)?)),
             Rule::char => {
-                let Some(char_decl) = rule.into_inner().next() else {
-                    return None;
-                };
+                let char_decl = rule.into_inner().next()?;
 
                 kind = Some(DefinitionType::Char(CharDecl::parse(char_decl)?))
             }
@@ -146,16 +522,33 @@ impl Parse for Definition {
                 let mut exprs = vec![];
 
                 for expr in rule.into_inner() {
-                    match expr.as_rule() {
-                        Rule::expr => {
-                            exprs.push(Expression::parse(expr)?);
-                        }
-                        _ => {}
+                    if expr.as_rule() == Rule::expr {
+                        exprs.push(Expression::parse(expr)?);
                     }
                 }
 
                 kind = Some(DefinitionType::Array(exprs))
             }
+            Rule::array_repeat => {
+                let mut exprs = rule
+                    .into_inner()
+                    .filter(|n| n.as_rule() == Rule::expr)
+                    .map(Expression::parse);
+
+                kind = Some(DefinitionType::ArrayRepeat {
+                    value: Box::new(exprs.next()??),
+                    count: Box::new(exprs.next()??),
+                })
+            }
+            Rule::tuple => {
+                let exprs = rule
+                    .into_inner()
+                    .filter(|n| n.as_rule() == Rule::expr)
+                    .map(Expression::parse)
+                    .collect::<Option<Vec<_>>>()?;
+
+                kind = Some(DefinitionType::Tuple(exprs))
+            }
             Rule::closure => kind = Some(DefinitionType::Closure(Closure::parse(rule)?)),
             Rule::range => kind = Some(DefinitionType::Range(Range::parse(rule)?)),
             _ => {}
@@ -163,20 +556,101 @@ impl Parse for Definition {
 
         Some(Definition { kind: kind? })
     }
-}
 
-impl Definition {
+    pub fn infer_type(&self) -> Option<Type> {
+        let which = match &self.kind {
+            DefinitionType::Bool(_) => WhichType::Bool,
+            DefinitionType::Int(int) => int.infer_type().which,
+            DefinitionType::Float(float) => float.infer_type().which,
+            DefinitionType::String(_) => WhichType::String,
+            DefinitionType::Char(_) => WhichType::Char,
+            DefinitionType::Array(arr) => match arr.first()?.infer_type()?.which {
+                WhichType::Array { ty, dimensions } => WhichType::Array {
+                    ty,
+                    dimensions: dimensions + 1,
+                },
+                which => WhichType::Array {
+                    ty: Box::new(Type::new(which)),
+                    dimensions: 1,
+                },
+            },
+            DefinitionType::ArrayRepeat { value, .. } => WhichType::Array {
+                ty: Box::new(value.infer_type()?),
+                dimensions: 1,
+            },
+            DefinitionType::Tuple(items) => WhichType::Tuple(
+                items
+                    .iter()
+                    .map(|n| n.infer_type())
+                    .collect::<Option<Vec<_>>>()?,
+            ),
+            DefinitionType::Struct(def) => {
+                let ident = rewrite::scope::resolve_self(&def.ident);
+                rewrite::scope::find_struct(&ident).filter(|s| s.generics.is_none())?;
+                WhichType::Ident(ident)
+            }
+            DefinitionType::Closure(closure) => WhichType::Closure(closure.infer_type()?),
+            DefinitionType::Range(_) => WhichType::Ident("Range".to_string()),
+        };
+
+        Some(Type::new(which))
+    }
+
+    /// `ty` is the type the value is expected to have, if known
+    pub fn rewrite_as(&self, ty: Option<&Type>) -> String {
+        let item = ty.and_then(|ty| ty.iter_item());
+
+        match (&self.kind, ty.map(|ty| &ty.which)) {
+            (DefinitionType::Closure(closure), Some(WhichType::Closure(data))) => {
+                closure.rewrite_as(Some(data))
+            }
+            (DefinitionType::Array(items), _) if item.is_some() => format!(
+                "Vec.from({})",
+                items.iter().map(|n| n.rewrite_as(item.as_ref())).join(", ")
+            ),
+            (DefinitionType::ArrayRepeat { value, count }, _) if item.is_some() => format!(
+                "Vec.repeat({}, {})",
+                value.rewrite_as(item.as_ref()),
+                count.rewrite_as(Some(&Type::new(WhichType::Int)))
+            ),
+            (DefinitionType::Tuple(items), Some(WhichType::Tuple(types)))
+                if items.len() == types.len() =>
+            {
+                format!(
+                    "new {}<>({})",
+                    rewrite::add_tuple(items.len()),
+                    items
+                        .iter()
+                        .zip(types)
+                        .map(|(item, ty)| item.rewrite_as(Some(ty)))
+                        .join(", ")
+                )
+            }
+            _ => self.rewrite(),
+        }
+    }
+
     pub fn rewrite(&self) -> String {
         match &self.kind {
             DefinitionType::Bool(b) => b.to_string(),
             DefinitionType::Char(c) => c.rewrite(),
-            DefinitionType::Float(f) => format!("((double) {})", f.to_string()),
+            DefinitionType::Float(f) => f.rewrite(),
             DefinitionType::String(s) => format!("\"{}\"", CharDecl::rewrite_many(s.clone(), "")),
-            DefinitionType::Int(i) => i.to_string(),
+            DefinitionType::Int(i) => i.rewrite(),
             DefinitionType::Struct(def) => def.rewrite(),
             DefinitionType::Array(arr) => {
                 format!("Vec.from({})", Expression::rewrite_many(arr.clone(), ", "))
             }
+            DefinitionType::ArrayRepeat { value, count } => format!(
+                "Vec.repeat({}, {})",
+                value.rewrite(),
+                count.rewrite_as(Some(&Type::new(WhichType::Int)))
+            ),
+            DefinitionType::Tuple(items) => format!(
+                "new {}<>({})",
+                rewrite::add_tuple(items.len()),
+                Expression::rewrite_many(items.clone(), ", ")
+            ),
             DefinitionType::Closure(closure) => closure.rewrite(),
             DefinitionType::Range(range) => range.rewrite(),
         }
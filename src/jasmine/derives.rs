@@ -0,0 +1,322 @@
+use super::*;
+
+/// The traits `#[derive(...)]` generates methods for
+const DERIVES: [&str; 6] = ["Debug", "Clone", "PartialEq", "Eq", "Hash", "Default"];
+
+/// The traits named in `#[derive(A, B)]`
+pub fn parse_derives(pair: Pair<'_, Rule>) -> Vec<String> {
+    pair.into_inner()
+        .filter(|rule| rule.as_rule() == Rule::ident)
+        .map(|rule| rule.as_str().to_string())
+        .collect()
+}
+
+/// Report the derives that can't be generated
+fn check_derives(ident: &str, derives: &[String]) {
+    for derive in derives.iter().filter(|n| !DERIVES.contains(&n.as_str())) {
+        rewrite::report(format!(
+            "Cannot derive `{}` for `{}`, expected one of {}",
+            derive,
+            ident,
+            DERIVES.join(", ")
+        ));
+    }
+}
+
+/// Report the fields that `#[derive(Clone)]` can't clone or `#[derive(PartialEq)]` can't compare,
+/// java would share them or compare them by reference instead
+fn check_fields<'a>(ident: &str, derive: &str, fields: impl Iterator<Item = (String, &'a Type)>) {
+    let required = match derive {
+        "Eq" => "PartialEq",
+        _ => derive,
+    };
+
+    for (field, ty) in fields.filter(|(_, ty)| !ty.supports_derive(required)) {
+        rewrite::report(format!(
+            "Cannot derive `{}` for `{}`, {} of type `{}` does not implement `{}`",
+            derive,
+            ident,
+            field,
+            ty.describe(),
+            required
+        ));
+    }
+}
+
+/// `equals` that compares `fields` after checking the class of `other`
+fn rewrite_equals(class: &str, generics: Option<&GenericArguments>, fields: &[String]) -> String {
+    let compared = fields
+        .iter()
+        .map(|field| format!("Objects.equals(this.{field}, o.{field})"))
+        .join(" && ");
+
+    let wildcards = match generics {
+        Some(generics) => format!("<{}>", generics.args.iter().map(|_| "?").join(", ")),
+        None => "".to_string(),
+    };
+
+    format!(
+        "
+		public boolean equals(Object other) {{
+			if (!(other instanceof {class})) {{
+				return false;
+			}}
+
+			{class}{wildcards} o = ({class}{wildcards}) other;
+			return {compared};
+		}}
+		",
+    )
+}
+
+impl Structure {
+    /// The java methods for `#[derive(...)]`
+    pub fn rewrite_derives(&self) -> String {
+        check_derives(&self.ident, &self.derives);
+
+        let derives = |derive: &str| self.derives.iter().any(|n| n == derive);
+        let ty = rewrite::self_type(&self.ident, self.generics.as_ref()).rewrite();
+        let generics = self
+            .generics
+            .as_ref()
+            .map(|n| n.rewrite(self.where_clause.as_ref()))
+            .unwrap_or_default();
+
+        // the constructor takes the fields sorted by name
+        let sorted = self
+            .fields
+            .iter()
+            .sorted_by(|a, b| a.ident.cmp(&b.ident))
+            .collect::<Vec<_>>();
+
+        let mut rewritten = "".to_string();
+
+        if derives("Debug") {
+            let fields = self
+                .fields
+                .iter()
+                .map(|field| {
+                    format!(
+                        "\"{}: \" + _debug_(this.{})",
+                        field.ident,
+                        rewrite_ident(&field.ident)
+                    )
+                })
+                .join(" + \", \" + ");
+
+            rewritten.push_str(&format!(
+                "
+				public String fmtDebug() {{
+					return \"{} {{ \" + {} + \" }}\";
+				}}
+				",
+                self.ident, fields
+            ));
+        }
+
+        let fields = || {
+            self.fields
+                .iter()
+                .map(|field| (format!("field `{}`", field.ident), &field.ty))
+        };
+
+        if derives("Clone") {
+            check_fields(&self.ident, "Clone", fields());
+
+            rewritten.push_str(&format!(
+                "
+				public {ty} clone() {{
+					return new {ty}({});
+				}}
+				",
+                sorted
+                    .iter()
+                    .map(|field| format!("_clone_(this.{})", rewrite_ident(&field.ident)))
+                    .join(", ")
+            ));
+        }
+
+        if derives("PartialEq") || derives("Eq") {
+            let derive = if derives("PartialEq") {
+                "PartialEq"
+            } else {
+                "Eq"
+            };
+            check_fields(&self.ident, derive, fields());
+
+            rewritten.push_str(&rewrite_equals(
+                &self.ident,
+                self.generics.as_ref(),
+                &self
+                    .fields
+                    .iter()
+                    .map(|field| rewrite_ident(&field.ident))
+                    .collect::<Vec<_>>(),
+            ));
+        }
+
+        if derives("Hash") {
+            rewritten.push_str(&format!(
+                "
+				public int hashCode() {{
+					return Objects.hash({});
+				}}
+				",
+                self.fields
+                    .iter()
+                    .map(|field| format!("this.{}", rewrite_ident(&field.ident)))
+                    .join(", ")
+            ));
+        }
+
+        if derives("Default") {
+            let values = sorted
+                .iter()
+                .map(|field| {
+                    field.ty.default_value().unwrap_or_else(|| {
+                        rewrite::report(format!(
+                            "Cannot derive `Default` for `{}`, field `{}` of type `{}` has no default value",
+                            self.ident,
+                            field.ident,
+                            field.ty.describe()
+                        ));
+                        "null".to_string()
+                    })
+                })
+                .join(", ");
+
+            rewritten.push_str(&format!(
+                "
+				public static {generics} {ty} default_value() {{
+					return new {ty}({values});
+				}}
+				",
+            ));
+        }
+
+        rewritten
+    }
+}
+
+impl Enumeration {
+    /// The java methods for `#[derive(...)]`
+    pub fn rewrite_derives(&self) -> String {
+        check_derives(&self.ident, &self.derives);
+
+        let derives = |derive: &str| self.derives.iter().any(|n| n == derive);
+        let ident = rewrite_ident(&self.ident);
+        let ty = rewrite::self_type(&ident, self.generics.as_ref()).rewrite();
+        let generics = self
+            .generics
+            .as_ref()
+            .map(|n| n.rewrite(self.where_clause.as_ref()))
+            .unwrap_or_default();
+
+        // the fields holding variant data, in the order the constructor takes them
+        let data = self
+            .variants
+            .iter()
+            .filter(|variant| variant.data.is_some())
+            .map(|variant| format!("{}Data", rewrite_ident(&variant.ident)))
+            .collect::<Vec<_>>();
+
+        let mut rewritten = "".to_string();
+
+        if derives("Debug") {
+            let cases = self
+                .variants
+                .iter()
+                .map(|variant| match variant.data {
+                    Some(_) => format!(
+                        "case _{}: return \"{}(\" + _debug_(this.{}Data) + \")\";",
+                        variant.ident,
+                        variant.ident,
+                        rewrite_ident(&variant.ident)
+                    ),
+                    None => format!("case _{}: return \"{}\";", variant.ident, variant.ident),
+                })
+                .join("\n");
+
+            rewritten.push_str(&format!(
+                "
+				public String fmtDebug() {{
+					switch (this.currentVariant) {{
+						{cases}
+						default: return _matchFailed_();
+					}}
+				}}
+				",
+            ));
+        }
+
+        let variants = || {
+            self.variants.iter().filter_map(|variant| {
+                Some((
+                    format!("variant `{}`", variant.ident),
+                    variant.data.as_ref()?,
+                ))
+            })
+        };
+
+        if derives("Clone") {
+            check_fields(&self.ident, "Clone", variants());
+
+            rewritten.push_str(&format!(
+                "
+				public {ty} clone() {{
+					return new {ty}(this.currentVariant{});
+				}}
+				",
+                data.iter()
+                    .map(|field| format!(", _clone_(this.{})", field))
+                    .join("")
+            ));
+        }
+
+        if derives("PartialEq") || derives("Eq") {
+            let derive = if derives("PartialEq") {
+                "PartialEq"
+            } else {
+                "Eq"
+            };
+            check_fields(&self.ident, derive, variants());
+
+            let mut fields = vec!["currentVariant".to_string()];
+            fields.extend(data.iter().cloned());
+
+            rewritten.push_str(&rewrite_equals(&ident, self.generics.as_ref(), &fields));
+        }
+
+        if derives("Hash") {
+            rewritten.push_str(&format!(
+                "
+				public int hashCode() {{
+					return Objects.hash(this.currentVariant{});
+				}}
+				",
+                data.iter()
+                    .map(|field| format!(", this.{}", field))
+                    .join("")
+            ));
+        }
+
+        if derives("Default") {
+            match self.variants.iter().find(|variant| variant.is_default) {
+                Some(variant) if variant.data.is_none() => rewritten.push_str(&format!(
+                    "
+					public static {generics} {ty} default_value() {{
+						return {}();
+					}}
+					",
+                    variant.ident
+                )),
+                _ => rewrite::report(format!(
+                    "Cannot derive `Default` for `{}`, mark a variant without data `#[default]`",
+                    self.ident
+                )),
+            }
+        }
+
+        rewritten
+    }
+}
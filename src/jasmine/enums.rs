@@ -4,17 +4,21 @@ use super::*;
 pub struct EnumVariant {
     pub ident: String,
     pub data: Option<Type>,
+    /// marked `#[default]`, for `#[derive(Default)]`
+    pub is_default: bool,
 }
 
 impl Parse for EnumVariant {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut ident = None;
         let mut data = None;
+        let mut is_default = false;
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
                 Rule::ident => ident = Some(rule.as_str().to_string()),
                 Rule::ty => data = Some(Type::parse(rule)?),
+                Rule::default_attr => is_default = true,
                 _ => {}
             }
         }
@@ -22,6 +26,7 @@ impl Parse for EnumVariant {
         Some(Self {
             ident: ident?,
             data,
+            is_default,
         })
     }
 }
@@ -29,23 +34,29 @@ impl Parse for EnumVariant {
 #[derive(Clone, Debug, PartialEq)]
 pub struct Enumeration {
     pub ident: String,
+    pub visibility: Visibility,
     pub variants: Vec<EnumVariant>,
 
     pub generics: Option<GenericArguments>,
     pub where_clause: Option<Vec<WhereUnit>>,
+    /// the traits of `#[derive(...)]`
+    pub derives: Vec<String>,
 }
 
 impl Parse for Enumeration {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut ident = None;
+        let mut visibility = Visibility::Private;
         let mut variants = vec![];
 
         let mut generics = None;
         let mut where_clause = None;
+        let mut derives = vec![];
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
                 Rule::ident => ident = Some(rule.as_str().to_string()),
+                Rule::visibility => visibility = Visibility::parse(rule)?,
                 Rule::enum_variant => variants.push(EnumVariant::parse(rule)?),
 
                 Rule::generic_args => {
@@ -54,33 +65,41 @@ impl Parse for Enumeration {
                 Rule::where_clause => {
                     where_clause = Some(WhereUnit::parse_many(rule)?);
                 }
+                Rule::derive_attr => derives.extend(parse_derives(rule)),
                 _ => {}
             }
         }
 
         Some(Self {
             ident: ident?,
+            visibility,
             variants,
             generics,
             where_clause,
+            derives,
         })
     }
 }
 
 impl Enumeration {
-    pub fn rewrite_no_closing(&self) -> String {
+    pub fn rewrite_no_closing(&self, implements: &str) -> String {
         let generics = match &self.generics {
             Some(g) => g.rewrite(self.where_clause.as_ref()),
             None => "".to_string(),
         };
 
-        let fulltype = format!("{}{}", rewrite_ident(&self.ident), generics);
+        // the type without its bounds, for return types and `new`
+        let fulltype =
+            rewrite::self_type(&rewrite_ident(&self.ident), self.generics.as_ref()).rewrite();
 
         let mut rewritten = format!(
             "
-			public static class {} {{
+			{}static class {}{}{} {{
 			",
-            fulltype
+            self.visibility.rewrite(),
+            rewrite_ident(&self.ident),
+            generics,
+            implements
         );
 
         for (idx, variant) in self.variants.iter().enumerate() {
@@ -114,13 +133,10 @@ impl Enumeration {
 			}}
 			",
             self.ident,
-            format!(
-                ", {}",
-                variant_data
-                    .iter()
-                    .map(|(ident, ty)| format!("{} _{}Data", ty.rewrite(), ident))
-                    .join(", ")
-            ),
+            variant_data
+                .iter()
+                .map(|(ident, ty)| format!(", {} _{}Data", ty.rewrite(), ident))
+                .join(""),
             variant_data
                 .iter()
                 .map(|(ident, _)| format!("this.{}Data = _{}Data;", ident, ident))
@@ -131,7 +147,7 @@ impl Enumeration {
             rewritten.push_str(&format!(
                 "
 				public static {generics} {fulltype} {}({}) {{
-					return new {fulltype}({}, {});
+					return new {fulltype}(_{}{});
 				}}
 				",
                 variant.ident,
@@ -139,17 +155,17 @@ impl Enumeration {
                     Some(data) => format!("{} data", data.rewrite()),
                     None => "".to_string(),
                 },
-                format!("_{}", variant.ident),
+                variant.ident,
                 variant_data
                     .iter()
                     .map(|(ident, _)| {
                         if ident == &variant.ident {
-                            "data".to_string()
+                            ", data".to_string()
                         } else {
-                            "null".to_string()
+                            ", null".to_string()
                         }
                     })
-                    .join(", ")
+                    .join("")
             ))
         }
 
@@ -1,3 +1,5 @@
+use std::iter::Peekable;
+
 use super::*;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -9,43 +11,346 @@ pub struct FullExpr {
 
 impl Parse for FullExpr {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        let mut lhs = None;
-        let mut rhs = None;
-        let mut op = None;
-        let mut on_rhs = false;
+        let mut operands = vec![];
+        let mut ops = vec![];
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
-                Rule::op_expr_recurse if !on_rhs => {
-                    lhs = Some(Expression::parse(rule)?);
+                Rule::op_expr_recurse => operands.push(Expression::parse(rule)?),
+                Rule::two_input_op => ops.push(BinaryOperator::parse(rule)?),
+                _ => {}
+            }
+        }
+
+        let mut operands = operands.into_iter();
+        let first = operands.next()?;
+
+        match FullExpr::climb(first, &mut ops.into_iter().zip(operands).peekable(), 0) {
+            Expression::Full(full) => Some(full),
+            _ => None,
+        }
+    }
+}
+
+impl FullExpr {
+    /// Precedence climbing: fold `rest` onto `lhs` while its operators bind at least as
+    /// tightly as `min_precedence`, so that `a - b * c - d` is `(a - (b * c)) - d`
+    fn climb(
+        mut lhs: Expression,
+        rest: &mut Peekable<impl Iterator<Item = (BinaryOperator, Expression)>>,
+        min_precedence: u8,
+    ) -> Expression {
+        while let Some((op, _)) = rest.peek() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+
+            let Some((op, mut rhs)) = rest.next() else {
+                break;
+            };
+
+            while let Some((next, _)) = rest.peek() {
+                if next.precedence() <= op.precedence() {
+                    break;
+                }
+
+                rhs = FullExpr::climb(rhs, rest, op.precedence() + 1);
+            }
+
+            lhs = Expression::Full(FullExpr {
+                lhs: Box::new(lhs),
+                op,
+                rhs: Box::new(rhs),
+            });
+        }
+
+        lhs
+    }
+
+    /// Whether the result of this operator is one of its operands' type, unlike comparisons
+    fn is_arithmetic(&self) -> bool {
+        !matches!(
+            self.op,
+            BinaryOperator::And
+                | BinaryOperator::Or
+                | BinaryOperator::Eq
+                | BinaryOperator::Neq
+                | BinaryOperator::Lt
+                | BinaryOperator::Gt
+                | BinaryOperator::Lte
+                | BinaryOperator::Gte
+        )
+    }
+
+    /// The java type to cast the result back to, since java does `byte` and `short` arithmetic in `int`
+    fn narrowed(&self) -> Option<String> {
+        if !self.is_arithmetic() {
+            return None;
+        }
+
+        match self.infer_type()?.which {
+            WhichType::I8 | WhichType::I16 => self.infer_type()?.rewrite_primitive(),
+            _ => None,
+        }
+    }
+
+    /// Parenthesize an operand only where java would otherwise group it differently.
+    /// Unsuffixed literals are written as `ty`, e.g. the `2.0` in `x * 2.0` for an `f32` x
+    fn rewrite_operand(&self, operand: &Expression, is_rhs: bool, ty: Option<&Type>) -> String {
+        let Expression::Full(inner) = operand else {
+            return match operand.is_unsuffixed_literal() {
+                true => operand.rewrite_as(ty),
+                false => operand.rewrite(),
+            };
+        };
+
+        let outer_precedence = self.op.java_precedence();
+        let inner_precedence = inner.op.java_precedence();
+
+        if inner.narrowed().is_none()
+            && (inner_precedence < outer_precedence
+                || (is_rhs && inner_precedence == outer_precedence))
+        {
+            format!("({})", inner.rewrite())
+        } else {
+            inner.rewrite()
+        }
+    }
+
+    /// The types of both operands, where an unsuffixed literal takes the type of the
+    /// other side, e.g. `x + 1` for an `i8` x is all `i8`
+    fn operand_types(&self) -> Option<(Type, Type)> {
+        let lhs = self.lhs.infer_type()?;
+        let rhs = self.rhs.infer_type()?;
+
+        if self.lhs.is_unsuffixed_literal() && self.lhs.fits_literal(&rhs) {
+            return Some((rhs.clone(), rhs));
+        }
+
+        if self.rhs.is_unsuffixed_literal() && self.rhs.fits_literal(&lhs) {
+            return Some((lhs.clone(), lhs));
+        }
+
+        Some((lhs, rhs))
+    }
+
+    /// `a && b` and `a || b`. Statements hoisted out of `b` must only run when `a` does not
+    /// decide the result, so then the result is computed in a temporary.
+    fn rewrite_logical(&self) -> Option<String> {
+        let negate = match self.op {
+            BinaryOperator::And => "",
+            BinaryOperator::Or => "!",
+            _ => return None,
+        };
+
+        let lhs = self.rewrite_operand(&self.lhs, false, None);
+        let (hoisted, rhs) =
+            rewrite::collect_hoisted(|| self.rewrite_operand(&self.rhs, true, None));
+
+        if hoisted.is_empty() {
+            return Some(format!("{} {} {}", lhs, self.op.rewrite(), rhs));
+        }
+
+        let target = rewrite::temp_ident();
+
+        rewrite::hoist(format!(
+            "boolean {} = {};\nif ({}{}) {{\n{}\n{} = {};\n}}",
+            target,
+            lhs,
+            negate,
+            target,
+            hoisted.join("\n"),
+            target,
+            rhs
+        ));
+
+        Some(target)
+    }
+
+    /// The receiver of a method call replacing this operator
+    fn rewrite_receiver(&self) -> String {
+        match self.lhs.as_ref() {
+            Expression::Full(full) => format!("({})", full.rewrite()),
+            lhs => lhs.rewrite(),
+        }
+    }
+
+    /// Rewrite both operands with `lhs` and `rhs`. If statements are hoisted out of the right
+    /// operand, the left one is computed in a temporary first so that it still runs first.
+    fn rewrite_in_order(
+        &self,
+        lhs: impl FnOnce() -> String,
+        rhs: impl FnOnce() -> String,
+    ) -> (String, String) {
+        let mut lhs = lhs();
+        let (hoisted, rhs) = rewrite::collect_hoisted(rhs);
+
+        if !hoisted.is_empty() && !self.lhs.is_constant() {
+            let target = rewrite::temp_ident();
+            rewrite::hoist(format!("final var {} = {};", target, lhs));
+            lhs = target;
+        }
+
+        hoisted.into_iter().for_each(rewrite::hoist);
+
+        (lhs, rhs)
+    }
+
+    /// `==` and `!=` on numbers, chars and bools compare the unboxed values at the wider type,
+    /// where java would compare the boxes by reference
+    fn rewrite_unboxed_eq(&self) -> Option<String> {
+        if !matches!(self.op, BinaryOperator::Eq | BinaryOperator::Neq) {
+            return None;
+        }
+
+        let (lhs, rhs) = self.operand_types()?;
+        let ty = match (&lhs.which, &rhs.which) {
+            (WhichType::Bool, WhichType::Bool) => lhs,
+            _ => lhs.wider(&rhs)?,
+        };
+        let primitive = ty.rewrite_primitive()?;
+
+        let unbox = |operand: &Expression| match operand {
+            _ if operand.is_unsuffixed_literal() => operand.rewrite_as(Some(&ty)),
+            Expression::Full(full) => format!("(({}) ({}))", primitive, full.rewrite()),
+            _ => format!("(({}) {})", primitive, operand.rewrite()),
+        };
+
+        let (lhs, rhs) = self.rewrite_in_order(|| unbox(&self.lhs), || unbox(&self.rhs));
+
+        Some(format!("{} {} {}", lhs, self.op.rewrite(), rhs))
+    }
+
+    /// Operators on objects, as method calls: `a + b` through `impl Add` is `a.add(b)`,
+    /// `==` is `equals` and `<` is `compareTo`. Only `==` on numbers, chars and bools stays `==`
+    fn rewrite_overloaded(&self) -> Option<String> {
+        let lhs = self.lhs.infer_type();
+        let negate = match self.op {
+            BinaryOperator::Neq => "!",
+            _ => "",
+        };
+
+        let method = lhs
+            .as_ref()
+            .zip(self.op.overload())
+            .and_then(|(ty, std_trait)| ty.operator_method(std_trait));
+
+        if let (Some(ty), Some(method)) = (&lhs, method) {
+            let (receiver, rhs) = self.rewrite_in_order(
+                || self.rewrite_receiver(),
+                || self.rhs.rewrite_as(ty.method_params(method).first()),
+            );
+
+            return Some(format!("{}.{}({})", receiver, method, rhs));
+        }
+
+        match self.op {
+            BinaryOperator::Eq | BinaryOperator::Neq
+                if lhs.as_ref().is_some_and(|ty| ty.has_equals()) =>
+            {
+                let (receiver, rhs) =
+                    self.rewrite_in_order(|| self.rewrite_receiver(), || self.rhs.rewrite());
+
+                Some(format!("{}{}.equals({})", negate, receiver, rhs))
+            }
+            // anything but known primitives, e.g. a type parameter that may hold `null`
+            BinaryOperator::Eq | BinaryOperator::Neq => self.rewrite_unboxed_eq().or_else(|| {
+                let (lhs, rhs) =
+                    self.rewrite_in_order(|| self.lhs.rewrite(), || self.rhs.rewrite());
+
+                Some(format!("{}Objects.equals({}, {})", negate, lhs, rhs))
+            }),
+            BinaryOperator::Lt | BinaryOperator::Gt | BinaryOperator::Lte | BinaryOperator::Gte
+                if lhs.as_ref().is_some_and(|ty| ty.is_comparable()) =>
+            {
+                let (receiver, rhs) =
+                    self.rewrite_in_order(|| self.rewrite_receiver(), || self.rhs.rewrite());
+
+                Some(format!(
+                    "{}.compareTo({}) {} 0",
+                    receiver,
+                    rhs,
+                    self.op.rewrite()
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn infer_type(&self) -> Option<Type> {
+        if !self.is_arithmetic() {
+            return Some(Type::new(WhichType::Bool));
+        }
+
+        if let Some(std_trait) = self.op.overload() {
+            let lhs = self.lhs.infer_type()?;
+
+            if let Some(method) = lhs.operator_method(std_trait) {
+                return lhs.method_returns(&FunctionCall {
+                    ident: method.to_string(),
+                    args: vec![],
+                });
+            }
+        }
+
+        match self.op {
+            BinaryOperator::Shl | BinaryOperator::Shr => {
+                let lhs = self.lhs.infer_type()?;
+
+                match lhs.which {
+                    WhichType::Char => Some(Type::new(WhichType::Int)),
+                    _ if lhs.is_integer() => Some(lhs),
+                    _ => None,
                 }
-                Rule::op_expr_recurse if on_rhs => {
-                    rhs = Some(Expression::parse(rule)?);
+            }
+            BinaryOperator::BitAnd | BinaryOperator::BitOr | BinaryOperator::BitXor => {
+                let (lhs, rhs) = self.operand_types()?;
+
+                // non short-circuiting on bools, bitwise on integers
+                match (&lhs.which, &rhs.which) {
+                    (WhichType::Bool, WhichType::Bool) => Some(lhs),
+                    _ => lhs.wider(&rhs).filter(|ty| ty.is_integer()),
                 }
-                Rule::two_input_op => {
-                    op = Some(BinaryOperator::parse(rule)?);
-                    on_rhs = true;
+            }
+            _ => {
+                let (lhs, rhs) = self.operand_types()?;
+
+                match (&lhs.which, &rhs.which) {
+                    (WhichType::String, _) | (_, WhichType::String)
+                        if self.op == BinaryOperator::Add =>
+                    {
+                        Some(Type::new(WhichType::String))
+                    }
+                    _ => lhs.wider(&rhs),
                 }
-                _ => {}
             }
         }
-
-        Some(FullExpr {
-            lhs: Box::new(lhs?),
-            op: op?,
-            rhs: Box::new(rhs?),
-        })
     }
 }
 
 impl Rewrite for FullExpr {
     fn rewrite(&self) -> String {
-        format!(
-            "({} {} {})",
-            self.lhs.rewrite(),
-            self.op.rewrite(),
-            self.rhs.rewrite()
-        )
+        if let Some(rewritten) = self.rewrite_overloaded().or_else(|| self.rewrite_logical()) {
+            return rewritten;
+        }
+
+        let ty = match self.op {
+            BinaryOperator::Shl | BinaryOperator::Shr => None,
+            _ if self.is_arithmetic() => self.infer_type(),
+            _ => None,
+        };
+
+        let (lhs, rhs) = self.rewrite_in_order(
+            || self.rewrite_operand(&self.lhs, false, ty.as_ref()),
+            || self.rewrite_operand(&self.rhs, true, ty.as_ref()),
+        );
+
+        let rewritten = format!("{} {} {}", lhs, self.op.rewrite(), rhs);
+
+        match self.narrowed() {
+            Some(primitive) => format!("(({}) ({}))", primitive, rewritten),
+            None => rewritten,
+        }
     }
 }
 
@@ -63,9 +368,92 @@ pub enum AfterDotExprType {
         data: Expression,
         after_dot: Option<Box<AfterDotExprType>>,
     },
+    TupleIdx {
+        data: usize,
+        after_dot: Option<Box<AfterDotExprType>>,
+    },
+}
+
+impl Parse for AfterDotExprType {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        match pair.as_rule() {
+            Rule::object_fn => {
+                let fn_rule = pair.into_inner().find(|n| n.as_rule() == Rule::fn_call)?;
+
+                Some(AfterDotExprType::ObjectFnCall {
+                    data: FunctionCall::parse(fn_rule)?,
+                    after_dot: None,
+                })
+            }
+            Rule::object_prop => {
+                let ident = pair.into_inner().find(|n| n.as_rule() == Rule::ident)?;
+
+                Some(AfterDotExprType::ObjectProp {
+                    data: ident.as_str().to_string(),
+                    after_dot: None,
+                })
+            }
+            Rule::tuple_idx => {
+                let field = pair
+                    .into_inner()
+                    .find(|n| n.as_rule() == Rule::tuple_field)?;
+
+                Some(AfterDotExprType::TupleIdx {
+                    data: field.as_str().parse().ok()?,
+                    after_dot: None,
+                })
+            }
+            Rule::array_idx => {
+                let expr = pair.into_inner().find(|n| n.as_rule() == Rule::expr)?;
+
+                Some(AfterDotExprType::ArrayIdx {
+                    data: Expression::parse(expr)?,
+                    after_dot: None,
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
 impl AfterDotExprType {
+    fn next(&self) -> Option<&AfterDotExprType> {
+        match self {
+            AfterDotExprType::ObjectFnCall { after_dot, .. }
+            | AfterDotExprType::ObjectProp { after_dot, .. }
+            | AfterDotExprType::ArrayIdx { after_dot, .. }
+            | AfterDotExprType::TupleIdx { after_dot, .. } => after_dot.as_deref(),
+        }
+    }
+
+    /// Whether this chain only reads fields, so it names the same place every time
+    pub fn is_path(&self) -> bool {
+        match self {
+            AfterDotExprType::ObjectProp { .. } | AfterDotExprType::TupleIdx { .. } => {
+                self.next().is_none_or(|n| n.is_path())
+            }
+            _ => false,
+        }
+    }
+
+    /// This chain without its last access, and the last access on its own
+    pub fn split_last(&self) -> (Option<AfterDotExprType>, AfterDotExprType) {
+        let mut head = self.clone();
+        head.take_after_dot();
+
+        let Some(next) = self.next() else {
+            return (None, head);
+        };
+
+        let (rest, last) = next.split_last();
+
+        if let Some(rest) = rest {
+            head.push(rest);
+        }
+
+        (Some(head), last)
+    }
+
     pub fn push(&mut self, next: AfterDotExprType) {
         let new = Box::new(next);
 
@@ -91,40 +479,129 @@ impl AfterDotExprType {
                     *after_dot = Some(new);
                 }
             }
+            AfterDotExprType::TupleIdx { after_dot, .. } => {
+                if let Some(after_dot) = after_dot {
+                    after_dot.push(*new);
+                } else {
+                    *after_dot = Some(new);
+                }
+            }
         }
     }
 
-    pub fn rewrite(&self) -> String {
+    /// The type of this access chain when applied to a value of type `on`
+    pub fn infer_type(&self, on: Type) -> Option<Type> {
+        let (ty, after_dot) = match self {
+            AfterDotExprType::ObjectFnCall { data, after_dot } => {
+                (on.method_returns(data)?, after_dot)
+            }
+            AfterDotExprType::ObjectProp { data, after_dot } => (on.field(data)?, after_dot),
+            AfterDotExprType::ArrayIdx { after_dot, .. } => (on.iter_item()?, after_dot),
+            AfterDotExprType::TupleIdx { data, after_dot } => match &on.which {
+                WhichType::Tuple(items) => (items.get(*data)?.clone(), after_dot),
+                _ => return None,
+            },
+        };
+
+        match after_dot {
+            Some(after_dot) => after_dot.infer_type(ty),
+            None => Some(ty),
+        }
+    }
+
+    /// Report the private fields and methods this access uses on a value of type `on`
+    fn check_access(&self, on: &Type) {
+        let Some((outer, _)) = on.generic_parts() else {
+            return;
+        };
+
         match self {
-            AfterDotExprType::ObjectFnCall {
-                data, after_dot, ..
-            } => {
-                if let Some(after_dot) = after_dot {
-                    format!("{}.{}", data.rewrite(), after_dot.rewrite())
-                } else {
-                    data.rewrite()
-                }
+            AfterDotExprType::ObjectFnCall { data, .. } => {
+                rewrite::scope::check_method(&outer, &data.ident)
             }
-            AfterDotExprType::ObjectProp {
-                data, after_dot, ..
-            } => {
-                if let Some(after_dot) = after_dot {
-                    format!("{}.{}", rewrite_ident(data), after_dot.rewrite())
-                } else {
-                    rewrite_ident(data)
-                }
+            AfterDotExprType::ObjectProp { data, .. } => rewrite::scope::check_field(&outer, data),
+            _ => {}
+        }
+    }
+
+    /// Report the private fields and methods of the whole chain, for places that are not rewritten with `rewrite_on`
+    pub fn check_chain(&self, on: Type) {
+        self.check_access(&on);
+
+        let mut head = self.clone();
+
+        if let (Some(next), Some(ty)) = (head.take_after_dot(), head.infer_type(on)) {
+            next.check_chain(ty);
+        }
+    }
+
+    pub fn rewrite(&self) -> String {
+        self.rewrite_on(None)
+    }
+
+    /// `on` is the type this access chain is applied to, if known
+    pub fn rewrite_on(&self, on: Option<Type>) -> String {
+        if let Some(on) = &on {
+            self.check_access(on);
+        }
+
+        let (rewritten, after_dot) = match self {
+            AfterDotExprType::ObjectFnCall { data, after_dot } => {
+                let params = on
+                    .as_ref()
+                    .map(|on| on.method_params(&data.ident))
+                    .unwrap_or_default();
+
+                (data.rewrite_with_params(&params), after_dot)
             }
-            AfterDotExprType::ArrayIdx { data, after_dot } => {
-                if let Some(after_dot) = after_dot {
-                    format!(
-                        "get((Integer) {}).unwrap().{}",
-                        data.rewrite(),
-                        after_dot.rewrite()
-                    )
-                } else {
-                    format!("get((Integer) {}).unwrap()", data.rewrite())
-                }
+            AfterDotExprType::ObjectProp { data, after_dot } => (rewrite_ident(data), after_dot),
+            AfterDotExprType::ArrayIdx { data, after_dot } => (
+                format!("get((Integer) {}).unwrap()", data.rewrite()),
+                after_dot,
+            ),
+            AfterDotExprType::TupleIdx { data, after_dot } => (format!("_{}", data), after_dot),
+        };
+
+        let Some(after_dot) = after_dot else {
+            return rewritten;
+        };
+
+        let mut head = self.clone();
+        head.take_after_dot();
+        let next = on.and_then(|on| head.infer_type(on));
+
+        format!("{}.{}", rewritten, after_dot.rewrite_on(next))
+    }
+
+    /// `receiver` followed by this access chain, where `on` is the type of `receiver` if known.
+    /// `.clone()` goes through `_clone_` where java has no public `clone`
+    pub fn rewrite_after(&self, receiver: String, on: Option<Type>) -> String {
+        let mut head = self.clone();
+        let rest = head.take_after_dot();
+
+        let rewritten = match &head {
+            AfterDotExprType::ObjectFnCall { data, .. }
+                if data.ident == "clone"
+                    && data.args.is_empty()
+                    && on.as_ref().is_some_and(|on| on.clones_with_helper()) =>
+            {
+                format!("_clone_({})", receiver)
             }
+            _ => format!("{}.{}", receiver, head.rewrite_on(on.clone())),
+        };
+
+        match rest {
+            Some(rest) => rest.rewrite_after(rewritten, on.and_then(|on| head.infer_type(on))),
+            None => rewritten,
+        }
+    }
+
+    fn take_after_dot(&mut self) -> Option<Box<AfterDotExprType>> {
+        match self {
+            AfterDotExprType::ObjectFnCall { after_dot, .. }
+            | AfterDotExprType::ObjectProp { after_dot, .. }
+            | AfterDotExprType::ArrayIdx { after_dot, .. }
+            | AfterDotExprType::TupleIdx { after_dot, .. } => after_dot.take(),
         }
     }
 }
@@ -165,13 +642,245 @@ impl BaseExprType {
         }
     }
 
+    /// A function named without calling it, e.g. `is_even` or `Circle::area`, as a java method
+    /// reference. Its closure type is `None` if the function or its type is generic,
+    /// java infers the type arguments from where the reference is used.
+    fn fn_value(&self) -> Option<(String, Option<ClosureTypeData>)> {
+        let BaseExprType::Ident {
+            data,
+            static_fn: None,
+            unit_enum,
+            after_dot: None,
+        } = self
+        else {
+            return None;
+        };
+
+        let (class, ident, is_generic, args, returns) = match unit_enum {
+            Some(unit_enum) if !rewrite::scope::is_module(data) => {
+                let ty_ident = rewrite::scope::resolve_self(data);
+                let method = rewrite::scope::find_method(&ty_ident, unit_enum)?;
+
+                let type_generics = rewrite::scope::find_struct(&ty_ident)
+                    .and_then(|s| s.generics)
+                    .or_else(|| rewrite::scope::find_enum(&ty_ident).and_then(|e| e.generics));
+
+                let mut args = method.param_types();
+
+                // an instance method takes its receiver first
+                if !method.is_static {
+                    args.insert(0, Type::new(WhichType::Ident(ty_ident.clone())));
+                }
+
+                let is_generic = method.generics.is_some() || type_generics.is_some();
+
+                (ty_ident, unit_enum, is_generic, args, method.returns)
+            }
+            _ => {
+                // `module::item` is just `item`
+                let ident = unit_enum.as_ref().unwrap_or(data);
+
+                if rewrite::scope::lookup(ident).is_some() || rewrite::scope::alias(ident).is_some()
+                {
+                    return None;
+                }
+
+                let function = rewrite::scope::find_fn(ident)?;
+
+                (
+                    rewrite::scope::root_class(),
+                    ident,
+                    function.generics.is_some(),
+                    function.param_types(),
+                    function.returns,
+                )
+            }
+        };
+
+        let data = ClosureTypeData {
+            args: args
+                .into_iter()
+                .map(|ty| ClosureArgument { generic: false, ty })
+                .collect(),
+            ret: returns.map(|ty| Box::new(ClosureArgument { generic: false, ty })),
+        };
+
+        Some((
+            format!("{}::{}", class, rewrite_ident(ident)),
+            (!is_generic).then_some(data),
+        ))
+    }
+
+    pub fn is_fn_value(&self) -> bool {
+        self.fn_value().is_some()
+    }
+
+    pub fn infer_type(&self) -> Option<Type> {
+        if let Some((_, data)) = self.fn_value() {
+            return Some(Type::new(WhichType::Closure(data?)));
+        }
+
+        let (ty, after_dot) = match self {
+            BaseExprType::FnCall { data, after_dot } => (data.infer_type()?, after_dot),
+            BaseExprType::Ident {
+                data,
+                static_fn,
+                unit_enum,
+                after_dot,
+            } => {
+                let data = &rewrite::scope::resolve_self(data);
+
+                let ty = if rewrite::scope::is_module(data) {
+                    // modules are flattened, so `module::item` is just `item`
+                    match (static_fn, unit_enum) {
+                        (Some(static_fn), _) => static_fn.infer_type()?,
+                        (None, Some(unit_enum)) => rewrite::scope::lookup(unit_enum)?,
+                        (None, None) => return None,
+                    }
+                } else if let Some(constant) = unit_enum
+                    .as_ref()
+                    .and_then(|unit_enum| rewrite::scope::find_assoc_const(data, unit_enum))
+                {
+                    constant.ty
+                } else if unit_enum.is_some() {
+                    rewrite::scope::find_enum(data).filter(|e| e.generics.is_none())?;
+                    Type::new(WhichType::Ident(data.clone()))
+                } else if let Some(static_fn) = static_fn {
+                    match (data.as_str(), static_fn.ident.as_str()) {
+                        ("Option", "Some") => Type::new(WhichType::Generic {
+                            outer: Box::new(Type::new(WhichType::Ident(data.clone()))),
+                            inner: vec![static_fn.args.first()?.expr.infer_type()?],
+                        }),
+                        _ if rewrite::scope::find_enum(data).is_some_and(|e| {
+                            e.generics.is_none()
+                                && e.variants.iter().any(|v| v.ident == static_fn.ident)
+                        }) =>
+                        {
+                            Type::new(WhichType::Ident(data.clone()))
+                        }
+                        _ => {
+                            let returns =
+                                rewrite::scope::find_method_returns(data, &static_fn.ident)??;
+
+                            // like a generic function, the type arguments depend on the call site
+                            let mut params = rewrite::scope::type_params(data);
+                            params
+                                .extend(rewrite::scope::method_type_params(data, &static_fn.ident));
+
+                            if params.iter().any(|p| returns.mentions(p)) {
+                                return None;
+                            }
+
+                            returns
+                        }
+                    }
+                } else {
+                    rewrite::scope::lookup(data)?
+                };
+
+                (ty, after_dot)
+            }
+        };
+
+        match after_dot {
+            Some(after_dot) => after_dot.infer_type(ty),
+            None => Some(ty),
+        }
+    }
+
+    /// The parameter types of `ty_ident::static_fn(...)`, a static method or an enum variant.
+    /// The type parameters of `ty_ident` are taken from `expected`, the type of the call if
+    /// known, e.g. `i64` for `Option::Some(5)` used as an `Option<i64>`
+    fn static_params(
+        ty_ident: &str,
+        static_fn: &FunctionCall,
+        expected: Option<&Type>,
+    ) -> Vec<Type> {
+        let param = |ident: &str| Type::new(WhichType::Ident(ident.to_string()));
+        let result_params = || vec!["T".to_string(), "E".to_string()];
+
+        let (params, type_params) = match (ty_ident, static_fn.ident.as_str()) {
+            ("Option", "Some") => (vec![param("T")], vec!["T".to_string()]),
+            ("Result", "Ok") => (vec![param("T")], result_params()),
+            ("Result", "Err") => (vec![param("E")], result_params()),
+            _ => match rewrite::scope::find_method(ty_ident, &static_fn.ident) {
+                Some(method) => (method.param_types(), rewrite::scope::type_params(ty_ident)),
+                None => (
+                    rewrite::scope::find_enum(ty_ident)
+                        .and_then(|e| e.variants.into_iter().find(|v| v.ident == static_fn.ident))
+                        .and_then(|v| v.data)
+                        .into_iter()
+                        .collect(),
+                    rewrite::scope::type_params(ty_ident),
+                ),
+            },
+        };
+
+        match expected.and_then(|ty| ty.generic_parts()) {
+            Some((outer, inner)) if outer == ty_ident => params
+                .iter()
+                .map(|n| n.substitute(&type_params, &inner))
+                .collect(),
+            // the builtins are generic, their parameters are only known from `expected`
+            _ if matches!(ty_ident, "Option" | "Result") => vec![],
+            _ => params,
+        }
+    }
+
+    /// The type of this expression before any accesses after a dot
+    fn head_type(&self) -> Option<Type> {
+        let mut head = self.clone();
+
+        match &mut head {
+            BaseExprType::FnCall { after_dot, .. } | BaseExprType::Ident { after_dot, .. } => {
+                *after_dot = None
+            }
+        }
+
+        head.infer_type()
+    }
+
     pub fn rewrite(&self) -> String {
+        self.rewrite_as(None)
+    }
+
+    /// `expected` is the type this expression is expected to have, if known
+    pub fn rewrite_as(&self, expected: Option<&Type>) -> String {
+        // paths with `::` are checked when the program is loaded
+        match self {
+            BaseExprType::FnCall { data, .. } => rewrite::scope::check_name(&data.ident),
+            BaseExprType::Ident {
+                data,
+                static_fn: None,
+                unit_enum: None,
+                ..
+            } if rewrite::scope::alias(data).is_none() => rewrite::scope::check_name(data),
+            _ => {}
+        }
+
+        if let Some((reference, _)) = self.fn_value() {
+            if let BaseExprType::Ident {
+                data,
+                unit_enum: Some(unit_enum),
+                ..
+            } = self
+            {
+                rewrite::scope::check_method(&rewrite::scope::resolve_self(data), unit_enum);
+            }
+
+            return reference;
+        }
+
         match self {
             BaseExprType::FnCall { data, after_dot } => {
-                let mut formatted = data.rewrite();
+                let params = rewrite::scope::find_fn(&data.ident)
+                    .map(|f| f.param_types())
+                    .unwrap_or_default();
+
+                let mut formatted = data.rewrite_with_params(&params);
 
                 if let Some(after_dot) = after_dot {
-                    formatted.push_str(&format!(".{}", after_dot.rewrite()));
+                    formatted = after_dot.rewrite_after(formatted, self.head_type());
                 }
 
                 formatted
@@ -182,18 +891,49 @@ impl BaseExprType {
                 static_fn,
                 unit_enum,
             } => {
-                let mut formatted = rewrite_ident(data);
+                let data = &rewrite::scope::resolve_self(data);
 
-                if let Some(static_fn) = static_fn {
-                    formatted.push_str(&format!(".{}", static_fn.rewrite()))
-                }
+                let mut formatted =
+                    rewrite::scope::alias(data).unwrap_or_else(|| rewrite_ident(data));
+
+                if rewrite::scope::is_module(data) {
+                    // modules are flattened into the root class
+                    formatted = match (static_fn, unit_enum) {
+                        (Some(static_fn), _) => {
+                            let params = rewrite::scope::find_fn(&static_fn.ident)
+                                .map(|f| f.param_types())
+                                .unwrap_or_default();
+
+                            static_fn.rewrite_with_params(&params)
+                        }
+                        (None, Some(unit_enum)) => rewrite_ident(unit_enum),
+                        (None, None) => formatted,
+                    };
+                } else {
+                    if let Some(static_fn) = static_fn {
+                        rewrite::scope::check_method(data, &static_fn.ident);
+                        formatted.push_str(&format!(
+                            ".{}",
+                            static_fn.rewrite_with_params(&BaseExprType::static_params(
+                                data,
+                                static_fn,
+                                expected.filter(|_| after_dot.is_none()),
+                            ))
+                        ))
+                    }
 
-                if let Some(unit_enum) = unit_enum {
-                    formatted.push_str(&format!(".{}()", unit_enum)) // fn call under the hood
+                    if let Some(unit_enum) = unit_enum {
+                        if rewrite::scope::find_assoc_const(data, unit_enum).is_some() {
+                            rewrite::scope::check_const(data, unit_enum);
+                            formatted.push_str(&format!(".{}", rewrite_ident(unit_enum)))
+                        } else {
+                            formatted.push_str(&format!(".{}()", unit_enum)) // fn call under the hood
+                        }
+                    }
                 }
 
                 if let Some(after_dot) = after_dot {
-                    formatted.push_str(&format!(".{}", after_dot.rewrite()));
+                    formatted = after_dot.rewrite_after(formatted, self.head_type());
                 }
 
                 formatted
@@ -236,9 +976,7 @@ impl Parse for BaseExpr {
                     };
 
                     *static_fn = Some(FunctionCall::parse(
-                        rule.into_inner()
-                            .filter(|f| f.as_rule() == Rule::fn_call)
-                            .next()?,
+                        rule.into_inner().find(|f| f.as_rule() == Rule::fn_call)?,
                     )?);
                 }
                 Rule::unit_enum => {
@@ -248,50 +986,20 @@ impl Parse for BaseExpr {
 
                     *unit_enum = Some(
                         rule.into_inner()
-                            .filter(|f| f.as_rule() == Rule::ident)
-                            .next()?
+                            .find(|f| f.as_rule() == Rule::ident)?
                             .as_str()
                             .trim()
                             .to_string(),
                     );
                 }
-                Rule::object_fn => {
+                Rule::object_fn | Rule::object_prop | Rule::tuple_idx | Rule::array_idx => {
                     let Some(base_expr) = &mut kind else {
                         return None;
                     };
 
-                    let fn_rule = rule.into_inner().find(|n| n.as_rule() == Rule::fn_call)?;
-
-                    base_expr.push(AfterDotExprType::ObjectFnCall {
-                        data: FunctionCall::parse(fn_rule)?,
-                        after_dot: None,
-                    });
+                    base_expr.push(AfterDotExprType::parse(rule)?);
                 }
-                Rule::object_prop => {
-                    let Some(base_expr) = &mut kind else {
-                        return None;
-                    };
-
-                    let ident = rule.into_inner().find(|n| n.as_rule() == Rule::ident)?;
-
-                    base_expr.push(AfterDotExprType::ObjectProp {
-                        data: ident.as_str().to_string(),
-                        after_dot: None,
-                    });
-                }
-                Rule::array_idx => {
-                    let Some(base_expr) = &mut kind else {
-						return None;
-					};
-
-                    let expr = rule.into_inner().find(|n| n.as_rule() == Rule::expr)?;
-
-                    base_expr.push(AfterDotExprType::ArrayIdx {
-                        data: Expression::parse(expr)?,
-                        after_dot: None,
-                    });
-                }
-                Rule::base_expr => return Some(BaseExpr::parse(rule)?),
+                Rule::base_expr => return BaseExpr::parse(rule),
                 _ => {}
             }
         }
@@ -304,16 +1012,70 @@ impl Parse for BaseExpr {
 }
 
 impl BaseExpr {
+    /// The method `-x` calls through `impl Neg`, with the type of `x`
+    fn neg_method(&self) -> Option<(&'static str, Type)> {
+        if self.operators != [UnaryOperator::Neg] {
+            return None;
+        }
+
+        let ty = self.kind.infer_type()?;
+
+        Some((ty.operator_method("Neg")?, ty))
+    }
+
+    pub fn infer_type(&self) -> Option<Type> {
+        if let Some((method, ty)) = self.neg_method() {
+            return ty.method_returns(&FunctionCall {
+                ident: method.to_string(),
+                args: vec![],
+            });
+        }
+
+        let ty = self.kind.infer_type();
+
+        if self.operators.contains(&UnaryOperator::Not) {
+            return match ty {
+                Some(ty) if ty.which == WhichType::Char => Some(Type::new(WhichType::Int)),
+                Some(ty) if ty.is_integer() => Some(ty),
+                _ => Some(Type::new(WhichType::Bool)),
+            };
+        }
+
+        ty
+    }
+
+    /// `ty` is the type this expression is expected to have, if known
+    pub fn rewrite_as(&self, ty: Option<&Type>) -> String {
+        match self.operators.is_empty() {
+            true => self.kind.rewrite_as(ty),
+            false => self.rewrite(),
+        }
+    }
+
     pub fn rewrite(&self) -> String {
+        if let Some((method, _)) = self.neg_method() {
+            return format!("{}.{}()", self.kind.rewrite(), method);
+        }
+
         let mut rewritten = "".to_string();
+        let ty = self.kind.infer_type();
 
         for op in self.operators.iter() {
-            rewritten.push_str(&op.rewrite());
+            rewritten.push_str(&op.rewrite(ty.as_ref()));
         }
 
         rewritten.push_str(&self.kind.rewrite());
 
-        rewritten
+        // java negates a `byte` or `short` as an `int`
+        let narrowed = ty
+            .filter(|ty| matches!(ty.which, WhichType::I8 | WhichType::I16))
+            .filter(|_| !self.operators.is_empty())
+            .and_then(|ty| ty.rewrite_primitive());
+
+        match narrowed {
+            Some(primitive) => format!("(({}) {})", primitive, rewritten),
+            None => rewritten,
+        }
     }
 }
 
@@ -322,33 +1084,181 @@ pub enum Expression {
     Base(BaseExpr),
     Full(FullExpr),
     Definition(Definition),
+    Cast(Cast),
+    If(Box<IfValue>),
+    Match(Box<MatchStatement>),
 }
 
 impl Parse for Expression {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        let inner_pr = pair
-            .into_inner()
-            .filter(|r| r.as_rule() != Rule::lparen)
-            .next()?;
+        let inner_pr = pair.into_inner().find(|r| r.as_rule() != Rule::lparen)?;
 
         match inner_pr.as_rule() {
+            // a parenthesized expression
+            Rule::expr => Expression::parse(inner_pr),
             Rule::base_expr => Some(Expression::Base(BaseExpr::parse(inner_pr)?)),
             Rule::op_expr => Some(Expression::Full(FullExpr::parse(inner_pr)?)),
             Rule::definition => Some(Expression::Definition(Definition::parse(inner_pr)?)),
+            Rule::cast => Some(Expression::Cast(Cast::parse(inner_pr)?)),
+            Rule::if_value => Some(Expression::If(Box::new(IfValue::parse(inner_pr)?))),
+            Rule::match_value => Some(Expression::Match(Box::new(MatchStatement::parse(
+                inner_pr,
+            )?))),
             _ => None,
         }
     }
 }
 
 impl Expression {
+    pub fn infer_type(&self) -> Option<Type> {
+        match self {
+            Expression::Base(expr) => expr.infer_type(),
+            Expression::Definition(def) => def.infer_type(),
+            Expression::Full(expr) => expr.infer_type(),
+            Expression::Cast(cast) => cast.infer_type(),
+            Expression::If(if_value) => if_value.infer_type(),
+            Expression::Match(match_value) => match_value.infer_type(),
+        }
+    }
+
     pub fn rewrite(&self) -> String {
+        self.rewrite_as(None)
+    }
+
+    /// `ty` is the type the value is expected to have, if known
+    pub fn rewrite_as(&self, ty: Option<&Type>) -> String {
+        if let Some(converted) = ty.and_then(|ty| self.rewrite_converted(ty)) {
+            return converted;
+        }
+
         match self {
-            Expression::Base(expr) => expr.rewrite(),
-            Expression::Definition(def) => def.rewrite(),
+            Expression::Base(expr) => expr.rewrite_as(ty),
+            Expression::Definition(def) => def.rewrite_as(ty),
             Expression::Full(expr) => expr.rewrite(),
+            Expression::Cast(cast) => cast.rewrite(),
+            Expression::If(if_value) => if_value.rewrite(ty),
+            Expression::Match(match_value) => match_value.rewrite_value(ty),
         }
     }
 
+    /// Whether this can be computed at compile time, as the value of a `const` must be
+    pub fn is_constant(&self) -> bool {
+        match self {
+            Expression::Definition(def) => match &def.kind {
+                DefinitionType::Array(items) | DefinitionType::Tuple(items) => {
+                    items.iter().all(|n| n.is_constant())
+                }
+                DefinitionType::ArrayRepeat { value, count } => {
+                    value.is_constant() && count.is_constant()
+                }
+                DefinitionType::Struct(def) => def.fields.iter().all(|f| f.value.is_constant()),
+                DefinitionType::Range(range) => {
+                    range.begin.is_constant() && range.end.is_constant()
+                }
+                DefinitionType::Closure(_) => false,
+                _ => true,
+            },
+            Expression::Full(full) => full.lhs.is_constant() && full.rhs.is_constant(),
+            Expression::Cast(cast) => cast.expr.is_constant(),
+            Expression::Base(base) => match base.kind.as_ref() {
+                BaseExprType::Ident {
+                    data,
+                    static_fn: None,
+                    after_dot: None,
+                    unit_enum,
+                } => unit_enum.is_some() || rewrite::scope::find_const(data).is_some(),
+                _ => false,
+            },
+            Expression::If(_) | Expression::Match(_) => false,
+        }
+    }
+
+    /// Whether computing this twice gives the same value without doing anything else,
+    /// as for constants and variables
+    pub fn is_repeatable(&self) -> bool {
+        match self {
+            Expression::Base(base) => matches!(
+                base.kind.as_ref(),
+                BaseExprType::Ident {
+                    static_fn: None,
+                    after_dot: None,
+                    ..
+                }
+            ),
+            _ => self.is_constant(),
+        }
+    }
+
+    /// A number literal without a suffix, which takes the type it is used as
+    pub fn is_unsuffixed_literal(&self) -> bool {
+        match self {
+            Expression::Definition(def) => match &def.kind {
+                DefinitionType::Int(int) => int.suffix.is_none(),
+                DefinitionType::Float(float) => !float.suffixed,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether this unsuffixed literal can be written as `ty`, floats can not become integers
+    fn fits_literal(&self, ty: &Type) -> bool {
+        match self {
+            Expression::Definition(Definition {
+                kind: DefinitionType::Float(_),
+            }) => ty.numeric_rank().is_some() && !ty.is_integer(),
+            _ => ty.numeric_rank().is_some(),
+        }
+    }
+
+    /// Convert a number to the numeric type `ty`. Unsuffixed literals are written as `ty`,
+    /// narrower types are widened like java does, and narrowing needs an `as`
+    fn rewrite_converted(&self, ty: &Type) -> Option<String> {
+        ty.numeric_rank()?;
+
+        if let Expression::Definition(def) = self {
+            match &def.kind {
+                DefinitionType::Int(int) if int.suffix.is_none() => {
+                    return Some(int.rewrite_as(ty))
+                }
+                DefinitionType::Float(float)
+                    if self.is_unsuffixed_literal() && self.fits_literal(ty) =>
+                {
+                    let float = FloatLiteral {
+                        is_f32: ty.which == WhichType::F32,
+                        ..float.clone()
+                    };
+
+                    return Some(float.rewrite());
+                }
+                _ => {}
+            }
+        }
+
+        let source = self.infer_type()?;
+
+        if source == *ty || (source.numeric_rank().is_none() && source.which != WhichType::Char) {
+            return None;
+        }
+
+        if !source.widens_to(ty) {
+            rewrite::report(format!(
+                "Expected `{}` but found `{}`, use `as` to convert",
+                ty.describe(),
+                source.describe()
+            ));
+
+            return None;
+        }
+
+        let expr = match self {
+            Expression::Full(_) => format!("({})", self.rewrite()),
+            _ => self.rewrite(),
+        };
+
+        Some(format!("(({}) {})", ty.rewrite_primitive()?, expr))
+    }
+
     pub fn rewrite_many(all: Vec<Self>, sep: &'static str) -> String {
         all.iter().map(|e| e.rewrite()).join(sep)
     }
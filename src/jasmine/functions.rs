@@ -3,6 +3,7 @@ use super::*;
 #[derive(Clone, Debug, PartialEq)]
 pub struct Function {
     pub ident: String,
+    pub visibility: Visibility,
     pub args: Vec<Arg>,
     pub body: Vec<BlockPart>,
     pub returns: Option<Type>,
@@ -13,6 +14,7 @@ pub struct Function {
 impl Parse for Function {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut ident = None;
+        let mut visibility = Visibility::Private;
         let mut args = vec![];
         let mut body = vec![];
         let mut returns = None;
@@ -22,6 +24,7 @@ impl Parse for Function {
         for rule in pair.into_inner() {
             match rule.as_rule() {
                 Rule::ident => ident = Some(rule.as_str().to_string()),
+                Rule::visibility => visibility = Visibility::parse(rule)?,
                 Rule::define_arguments => {
                     args = Arg::parse_many(rule)?;
                 }
@@ -41,6 +44,7 @@ impl Parse for Function {
 
         Some(Function {
             ident: ident?,
+            visibility,
             args,
             body,
             returns,
@@ -51,6 +55,10 @@ impl Parse for Function {
 }
 
 impl Function {
+    pub fn param_types(&self) -> Vec<Type> {
+        self.args.iter().map(|arg| arg.ty.clone()).collect()
+    }
+
     pub fn rewrite(&self) -> String {
         let generics = self
             .generics
@@ -58,12 +66,28 @@ impl Function {
             .map(|n| n.rewrite(self.where_clause.as_ref()))
             .unwrap_or("".to_string());
 
+        let bounds = self
+            .generics
+            .as_ref()
+            .map(|n| n.all_bounds(self.where_clause.as_ref()))
+            .unwrap_or_default();
+
+        rewrite::scope::push();
+        Arg::declare_many(&self.args);
+        let body = rewrite::scope::with_bounds(bounds, || {
+            rewrite::scope::with_returns(self.returns.clone(), || {
+                BlockPart::rewrite_many(self.body.clone(), "\n")
+            })
+        });
+        rewrite::scope::pop();
+
         format!(
             "
-			public static {} {} {}({}) {{
+			{}static {} {} {}({}) {{
 				{}
 			}}
 			",
+            self.visibility.rewrite(),
             generics,
             self.returns
                 .as_ref()
@@ -71,15 +95,17 @@ impl Function {
                 .unwrap_or("void".to_string()),
             rewrite_ident(&self.ident),
             Arg::rewrite_many(self.args.clone(), ", "),
-            BlockPart::rewrite_many(self.body.clone(), "\n")
+            body
         )
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Closure {
-    pub args: Vec<Arg>,
+    pub args: Vec<ClosureParam>,
     pub body: Vec<BlockPart>,
+    /// the body of `|x| x * 2`, written without braces
+    pub value: Option<Box<Expression>>,
     pub returns: Option<Type>,
 }
 
@@ -87,16 +113,18 @@ impl Parse for Closure {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut args = vec![];
         let mut body = vec![];
+        let mut value = None;
         let mut returns = None;
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
-                Rule::define_arguments => {
-                    args = Arg::parse_many(rule)?;
+                Rule::closure_args => {
+                    args = ClosureParam::parse_many(rule)?;
                 }
                 Rule::block => {
                     body = BlockPart::parse_many(rule)?;
                 }
+                Rule::expr => value = Some(Box::new(Expression::parse(rule)?)),
                 Rule::ty => {
                     returns = Some(Type::parse(rule)?);
                 }
@@ -107,20 +135,161 @@ impl Parse for Closure {
         Some(Closure {
             args,
             body,
+            value,
             returns,
         })
     }
 }
 
 impl Closure {
+    /// Declare the arguments whose types are known, from the closure or from `expected`
+    fn declare_args(&self, expected: Option<&ClosureTypeData>) {
+        for (idx, arg) in self.args.iter().enumerate() {
+            let ty = arg.ty.clone().or_else(|| {
+                expected?
+                    .args
+                    .get(idx)
+                    .filter(|n| !n.generic)
+                    .map(|n| n.ty.clone())
+            });
+
+            if let Some(ty) = ty {
+                rewrite::scope::declare(&arg.ident, ty);
+            }
+        }
+    }
+
+    /// `None` if the type of an argument or of the value is unknown
+    pub fn infer_type(&self) -> Option<ClosureTypeData> {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| {
+                Some(ClosureArgument {
+                    generic: false,
+                    ty: arg.ty.clone()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let ret = match (&self.returns, &self.value) {
+            (Some(ty), _) => Some(ty.clone()),
+            (None, Some(value)) => {
+                rewrite::scope::push();
+                self.declare_args(None);
+                let ty = value.infer_type();
+                rewrite::scope::pop();
+
+                Some(ty?)
+            }
+            (None, None) => None,
+        };
+
+        Some(ClosureTypeData {
+            args,
+            ret: ret.map(|ty| Box::new(ClosureArgument { generic: false, ty })),
+        })
+    }
+
     pub fn rewrite(&self) -> String {
-        let args = Arg::rewrite_many(self.args.clone(), ", ");
-        let body = BlockPart::rewrite_many(self.body.clone(), "\n");
+        self.rewrite_as(None)
+    }
+
+    /// `expected` is the closure type this is passed as, which fills in left out types
+    pub fn rewrite_as(&self, expected: Option<&ClosureTypeData>) -> String {
+        // java does not allow mixing typed and untyped lambda parameters
+        let args = match self.args.iter().all(|arg| arg.ty.is_some()) {
+            true => self
+                .args
+                .iter()
+                .filter_map(|arg| {
+                    Some(format!(
+                        "{} {}",
+                        arg.ty.as_ref()?.rewrite(),
+                        rewrite_ident(&arg.ident)
+                    ))
+                })
+                .join(", "),
+            false => self
+                .args
+                .iter()
+                .map(|arg| rewrite_ident(&arg.ident))
+                .join(", "),
+        };
+
+        let expected_ret = expected.and_then(|n| n.ret.as_ref());
+
+        let returns = self
+            .returns
+            .clone()
+            .or_else(|| expected_ret.filter(|n| !n.generic).map(|n| n.ty.clone()));
+
+        rewrite::scope::push();
+        self.declare_args(expected);
+
+        let body = rewrite::scope::with_returns(returns.clone(), || {
+            let Some(value) = &self.value else {
+                return format!("{{{}}}", BlockPart::rewrite_many(self.body.clone(), "\n"));
+            };
+
+            let (hoisted, value) = rewrite::collect_hoisted(|| value.rewrite_as(returns.as_ref()));
+
+            if hoisted.is_empty() {
+                return value;
+            }
 
-        format!("({}) -> {{{}}}", args, body)
+            let has_value = match expected {
+                Some(_) => expected_ret.is_some(),
+                None => {
+                    returns.is_some() || self.value.as_ref().and_then(|v| v.infer_type()).is_some()
+                }
+            };
+
+            format!(
+                "{{\n{}\n{}{};\n}}",
+                hoisted.join("\n"),
+                if has_value { "return " } else { "" },
+                value
+            )
+        });
+
+        rewrite::scope::pop();
+
+        format!("({}) -> {}", args, body)
     }
 }
 
+/// The `{}` and `{:?}` in a format string, `{{` and `}}` are escaped braces like in `format` of
+/// Builtins.java
+fn count_placeholders(chars: &[CharDecl]) -> usize {
+    let text = chars
+        .iter()
+        .map(|n| match n {
+            CharDecl::RawChar(c) => *c,
+            CharDecl::EscapeChar(_) => ' ',
+        })
+        .collect::<String>();
+
+    let mut rest = text.as_str();
+    let mut count = 0;
+
+    while let Some(idx) = rest.find(['{', '}']) {
+        rest = &rest[idx..];
+
+        let skip = ["{{", "}}", "{}", "{:?}"]
+            .into_iter()
+            .find(|n| rest.starts_with(n))
+            .map_or(1, |n| {
+                count += usize::from(n == "{}" || n == "{:?}");
+                n.len()
+            });
+
+        rest = &rest[skip..];
+    }
+
+    count
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct FunctionCall {
     pub ident: String,
@@ -148,7 +317,58 @@ impl Parse for FunctionCall {
 }
 
 impl FunctionCall {
-    pub fn rewrite(&self) -> String {
+    /// The return type of calling a top-level function
+    pub fn infer_type(&self) -> Option<Type> {
+        if self.ident == "format" {
+            return Some(Type::new(WhichType::String));
+        }
+
+        let function = rewrite::scope::find_fn(&self.ident)?;
+        let returns = function.returns?;
+
+        // the return type depends on the call site
+        if let Some(generics) = function.generics {
+            if generics.args.iter().any(|g| returns.mentions(g)) {
+                return None;
+            }
+        }
+
+        Some(returns)
+    }
+
+    /// Report a literal format string of `print`, `println` or `format` whose placeholders don't
+    /// match the arguments after it
+    fn check_format(&self) {
+        if !["print", "println", "format"].contains(&self.ident.as_str())
+            || rewrite::scope::find_fn(&self.ident).is_some()
+        {
+            return;
+        }
+
+        let Some(Expression::Definition(Definition {
+            kind: DefinitionType::String(chars),
+        })) = self.args.first().map(|arg| &arg.expr)
+        else {
+            return;
+        };
+
+        let placeholders = count_placeholders(chars);
+        let given = self.args.len() - 1;
+
+        if placeholders != given {
+            rewrite::report(format!(
+                "The format string of `{}` has {} placeholder{}, but {} argument{} given",
+                self.ident,
+                placeholders,
+                if placeholders == 1 { "" } else { "s" },
+                given,
+                if given == 1 { " was" } else { "s were" }
+            ));
+        }
+    }
+
+    /// `params` are the declared parameter types of the callee, which the arguments are converted to
+    pub fn rewrite_with_params(&self, params: &[Type]) -> String {
         if self.ident == "panic" {
             return format!(
                 "throw new RuntimeException({})",
@@ -156,9 +376,18 @@ impl FunctionCall {
             );
         }
 
+        self.check_format();
+
         let mut rewritten = format!("{}(", rewrite_ident(&self.ident));
 
-        rewritten.push_str(&CallArg::rewrite_many((&self.args).clone(), ", "));
+        rewritten.push_str(
+            &self
+                .args
+                .iter()
+                .enumerate()
+                .map(|(idx, arg)| arg.expr.rewrite_as(params.get(idx)))
+                .join(", "),
+        );
 
         rewritten.push(')');
 
@@ -1,5 +1,27 @@
 use super::*;
 
+/// Rust traits that every java object already satisfies, through `toString`, `equals` and `hashCode`,
+/// and `Clone` and `Default`, which boxed numbers and strings could not satisfy as java interfaces.
+/// Values are cloned with `_clone_` instead.
+const OBJECT_TRAITS: [&str; 8] = [
+    "Display",
+    "Debug",
+    "PartialEq",
+    "Eq",
+    "Hash",
+    "Copy",
+    "Clone",
+    "Default",
+];
+
+/// The types of `A + B`
+fn parse_bounds(pair: Pair<'_, Rule>) -> Option<Vec<Type>> {
+    pair.into_inner()
+        .filter(|rule| rule.as_rule() == Rule::ty)
+        .map(Type::parse)
+        .collect()
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum WhereType {
     Extends,
@@ -10,19 +32,20 @@ pub enum WhereType {
 pub struct WhereUnit {
     pub kind: WhereType,
     pub generic: String,
-    pub constraint: String,
+    /// the bounds of `T: A + B`
+    pub constraints: Vec<Type>,
 }
 
 impl Parse for WhereUnit {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut generic = None;
-        let mut constraint = None;
+        let mut constraints = vec![];
         let mut kind = None;
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
-                Rule::ident if generic.is_none() => generic = Some(rule.as_str().to_string()),
-                Rule::ident if generic.is_some() => constraint = Some(rule.as_str().to_string()),
+                Rule::ident => generic = Some(rule.as_str().to_string()),
+                Rule::generic_bounds => constraints = parse_bounds(rule)?,
                 Rule::assign => kind = Some(WhereType::Extends),
                 Rule::colon => kind = Some(WhereType::Implements),
                 _ => {}
@@ -32,7 +55,7 @@ impl Parse for WhereUnit {
         Some(Self {
             kind: kind?,
             generic: generic?,
-            constraint: constraint?,
+            constraints,
         })
     }
 }
@@ -42,11 +65,8 @@ impl ParseMany for WhereUnit {
         let mut units = vec![];
 
         for rule in pair.into_inner() {
-            match rule.as_rule() {
-                Rule::where_unit => {
-                    units.push(WhereUnit::parse(rule)?);
-                }
-                _ => {}
+            if rule.as_rule() == Rule::where_unit {
+                units.push(WhereUnit::parse(rule)?);
             }
         }
 
@@ -54,27 +74,69 @@ impl ParseMany for WhereUnit {
     }
 }
 
+impl WhereUnit {
+    /// A bound as java writes it, `None` for the ones java objects always satisfy
+    fn rewrite_constraint(&self, constraint: &Type) -> Option<String> {
+        let ident = match &constraint.which {
+            WhichType::Ident(ident) if rewrite::scope::find_trait(ident).is_none() => ident,
+            _ => return Some(constraint.rewrite()),
+        };
+
+        match ident.as_str() {
+            "Ord" | "PartialOrd" => Some(format!("Comparable<{}>", self.generic)),
+            n if OBJECT_TRAITS.contains(&n) => None,
+            _ => Some(constraint.rewrite()),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct GenericArguments {
     pub args: Vec<String>,
+    /// inline bounds, e.g. `<T: Ord>`
+    pub bounds: Vec<WhereUnit>,
 }
 
 impl Parse for GenericArguments {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut args = vec![];
+        let mut bounds = vec![];
 
         for rule in pair.into_inner() {
-            match rule.as_rule() {
-                Rule::ident => args.push(rule.as_str().to_string()),
-                _ => {}
+            if rule.as_rule() == Rule::generic_param {
+                let mut generic = None;
+
+                for param in rule.into_inner() {
+                    match param.as_rule() {
+                        Rule::ident => generic = Some(param.as_str().to_string()),
+                        Rule::generic_bounds => bounds.push(WhereUnit {
+                            kind: WhereType::Implements,
+                            generic: generic.clone()?,
+                            constraints: parse_bounds(param)?,
+                        }),
+                        _ => {}
+                    }
+                }
+
+                args.push(generic?);
             }
         }
 
-        Some(Self { args })
+        Some(Self { args, bounds })
     }
 }
 
 impl GenericArguments {
+    /// The inline bounds together with the ones of `where_clause`
+    pub fn all_bounds(&self, where_clause: Option<&Vec<WhereUnit>>) -> Vec<WhereUnit> {
+        self.bounds
+            .iter()
+            .chain(where_clause.into_iter().flatten())
+            .cloned()
+            .collect()
+    }
+
+    /// `<T extends A & B, U>`, with the bounds from inline and from `where_clause`
     pub fn rewrite(&self, where_clause: Option<&Vec<WhereUnit>>) -> String {
         let mut rewritten = "".to_string();
         rewritten.push('<');
@@ -82,18 +144,21 @@ impl GenericArguments {
         for arg in self.clone().args {
             rewritten.push_str(&arg);
 
-            if let Some(where_unit) = where_clause
-                .as_ref()
-                .map(|n| n.iter().find(|n| n.generic == arg))
-                .flatten()
-                .cloned()
-            {
-                match where_unit.kind {
-                    WhereType::Extends => rewritten.push_str(" extends "),
-                    WhereType::Implements => rewritten.push_str(" extends "),
-                }
-
-                rewritten.push_str(&where_unit.constraint);
+            let constraints = self
+                .all_bounds(where_clause)
+                .iter()
+                .filter(|unit| unit.generic == arg)
+                .flat_map(|unit| {
+                    unit.constraints
+                        .iter()
+                        .filter_map(|constraint| unit.rewrite_constraint(constraint))
+                })
+                .unique()
+                .join(" & ");
+
+            if !constraints.is_empty() {
+                rewritten.push_str(" extends ");
+                rewritten.push_str(&constraints);
             }
 
             rewritten.push(',')
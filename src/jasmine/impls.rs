@@ -3,36 +3,155 @@ use super::*;
 #[derive(Clone, Debug, PartialEq)]
 pub struct Impl {
     pub ident: String,
+    /// `Some` for `impl Trait for Ident`
+    pub trait_ty: Option<Type>,
     pub methods: Vec<ImplFunction>,
+    /// associated constants, e.g. `Self::PI2`
+    pub consts: Vec<Constant>,
+    /// the `<T>` of `impl<T>`
+    pub generics: Option<GenericArguments>,
+    /// the `<T>` of `Stack<T>`
+    pub ty_args: Option<GenericArguments>,
+    pub where_clause: Option<Vec<WhereUnit>>,
 }
 
 impl Parse for Impl {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut ident = None;
+        let mut trait_ty = None;
         let mut methods = vec![];
+        let mut consts = vec![];
+        let mut generics = None;
+        let mut ty_args = None;
+        let mut where_clause = None;
 
         for impl_part in pair.into_inner() {
             match impl_part.as_rule() {
                 Rule::ident => {
                     ident = Some(impl_part.as_str().to_string());
                 }
+                Rule::ty => {
+                    trait_ty = Some(Type::parse(impl_part)?);
+                }
                 Rule::impl_fn_def => {
                     methods.push(ImplFunction::parse(impl_part)?);
                 }
+                Rule::const_def => {
+                    consts.push(Constant::parse(impl_part)?);
+                }
+                Rule::generic_args if ident.is_none() => {
+                    generics = Some(GenericArguments::parse(impl_part)?);
+                }
+                Rule::generic_args => {
+                    ty_args = Some(GenericArguments::parse(impl_part)?);
+                }
+                Rule::where_clause => {
+                    where_clause = Some(WhereUnit::parse_many(impl_part)?);
+                }
                 _ => {}
             }
         }
 
+        // trait methods are as visible as the trait
+        if trait_ty.is_some() {
+            for method in methods.iter_mut() {
+                method.visibility = Visibility::Public;
+            }
+        }
+
         Some(Impl {
             ident: ident?,
+            trait_ty,
             methods,
+            consts,
+            generics,
+            ty_args,
+            where_clause,
         })
     }
 }
 
+/// Rust traits that become methods and operators instead of java interfaces
+const STD_TRAITS: [&str; 12] = [
+    "Display",
+    "Debug",
+    "Add",
+    "Sub",
+    "Mul",
+    "Div",
+    "Rem",
+    "Neg",
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+];
+
+impl Impl {
+    /// The trait of `impl Add for T` if it is one of `STD_TRAITS`,
+    /// `None` for other impls and for user traits of the same name
+    pub fn std_trait(&self) -> Option<&'static str> {
+        let (ident, _) = self.trait_ty.as_ref()?.generic_parts()?;
+
+        if rewrite::scope::find_trait(&ident).is_some() {
+            return None;
+        }
+
+        STD_TRAITS.into_iter().find(|n| *n == ident)
+    }
+
+    /// The method `impl Add for T` must have, e.g. `add`
+    pub fn std_method(&self) -> Option<&'static str> {
+        match self.std_trait()? {
+            "Add" => Some("add"),
+            "Sub" => Some("sub"),
+            "Mul" => Some("mul"),
+            "Div" => Some("div"),
+            "Rem" => Some("rem"),
+            "Neg" => Some("neg"),
+            "PartialEq" => Some("eq"),
+            "PartialOrd" => Some("partial_cmp"),
+            "Ord" => Some("cmp"),
+            _ => None,
+        }
+    }
+
+    /// `Display` or `Debug` for `impl Display for T`, where `fmt` renders the value for printing
+    pub fn format_trait(&self) -> Option<&'static str> {
+        self.std_trait()
+            .filter(|n| matches!(*n, "Display" | "Debug"))
+    }
+
+    /// The `fmt` method of a `Display` or `Debug` impl, named as the java method it becomes
+    pub fn format_method(&self, method: &ImplFunction) -> ImplFunction {
+        let format_trait = self.format_trait().unwrap_or_default();
+
+        if method.ident != "fmt"
+            || method.is_static
+            || !method.args.is_empty()
+            || method.returns.as_ref().map(|n| &n.which) != Some(&WhichType::String)
+        {
+            rewrite::report(format!(
+                "`impl {} for {}` must only have `fn fmt(&self) -> string`, found `fn {}`",
+                format_trait, self.ident, method.ident
+            ));
+        }
+
+        let mut method = method.clone();
+        method.ident = match format_trait {
+            "Display" => "to_string",
+            _ => "fmt_debug",
+        }
+        .to_string();
+
+        method
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ImplFunction {
     pub ident: String,
+    pub visibility: Visibility,
     pub args: Vec<Arg>,
     pub body: Vec<BlockPart>,
     pub returns: Option<Type>,
@@ -44,6 +163,7 @@ pub struct ImplFunction {
 impl Parse for ImplFunction {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut ident = None;
+        let mut visibility = Visibility::Private;
         let mut args = vec![];
         let mut body = vec![];
         let mut returns = None;
@@ -54,6 +174,7 @@ impl Parse for ImplFunction {
         for rule in pair.into_inner() {
             match rule.as_rule() {
                 Rule::ident => ident = Some(rule.as_str().to_string()),
+                Rule::visibility => visibility = Visibility::parse(rule)?,
                 Rule::impl_define_arguments => {
                     for arg_rule in rule.into_inner() {
                         match arg_rule.as_rule() {
@@ -83,6 +204,7 @@ impl Parse for ImplFunction {
 
         Some(ImplFunction {
             ident: ident?,
+            visibility,
             args,
             body,
             returns,
@@ -94,9 +216,58 @@ impl Parse for ImplFunction {
 }
 
 impl ImplFunction {
+    pub fn param_types(&self) -> Vec<Type> {
+        self.args.iter().map(|arg| arg.ty.clone()).collect()
+    }
+
+    /// Java static methods can't use the type parameters of their class, so they declare them again
+    pub fn redeclare_generics(
+        &mut self,
+        generics: &GenericArguments,
+        where_clause: Option<&Vec<WhereUnit>>,
+    ) {
+        let mut args = generics.args.clone();
+        let mut bounds = generics.bounds.clone();
+
+        if let Some(own) = self.generics.take() {
+            args.extend(
+                own.args
+                    .into_iter()
+                    .filter(|arg| !generics.args.contains(arg)),
+            );
+            bounds.extend(own.bounds);
+        }
+
+        let mut units = where_clause.cloned().unwrap_or_default();
+        units.extend(self.where_clause.take().unwrap_or_default());
+
+        self.generics = Some(GenericArguments { args, bounds });
+        self.where_clause = (!units.is_empty()).then_some(units);
+    }
+
     pub fn rewrite(&self) -> String {
+        self.rewrite_with_modifier(if self.is_static { "static" } else { "" })
+    }
+
+    /// `modifier` goes between the access modifier and the return type, e.g. `static` or `default`
+    pub fn rewrite_with_modifier(&self, modifier: &str) -> String {
         let args = Arg::rewrite_many(self.args.clone(), ", ");
-        let body = BlockPart::rewrite_many(self.body.clone(), "\n");
+
+        let bounds = self
+            .generics
+            .as_ref()
+            .map(|n| n.all_bounds(self.where_clause.as_ref()))
+            .unwrap_or_default();
+
+        rewrite::scope::push();
+        Arg::declare_many(&self.args);
+        let body = rewrite::scope::with_bounds(bounds, || {
+            rewrite::scope::with_returns(self.returns.clone(), || {
+                BlockPart::rewrite_many(self.body.clone(), "\n")
+            })
+        });
+        rewrite::scope::pop();
+
         let generics = self
             .generics
             .as_ref()
@@ -104,8 +275,9 @@ impl ImplFunction {
             .unwrap_or("".to_string());
 
         format!(
-            "public {} {} {} {}({}) {{\n{}\n}}",
-            if self.is_static { "static" } else { "" },
+            "{}{} {} {} {}({}) {{\n{}\n}}",
+            self.visibility.rewrite(),
+            modifier,
             generics,
             self.returns
                 .as_ref()
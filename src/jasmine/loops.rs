@@ -25,30 +25,51 @@ impl Parse for WhileLoop {
 
 impl WhileLoop {
     pub fn rewrite(&self) -> String {
+        let (hoisted, cond) = rewrite::collect_hoisted(|| self.cond.rewrite());
+        let body = BlockPart::rewrite_many(self.body.clone(), "\n");
+
+        if hoisted.is_empty() {
+            return format!("while ({}) {{\n{}\n}}", cond, body);
+        }
+
+        // statements hoisted out of the condition run before every check of it
         format!(
-            "while ({}) {{\n{}\n}}",
-            self.cond.rewrite(),
-            BlockPart::rewrite_many(self.body.clone(), "\n")
+            "while (true) {{\n{}\nif (!({})) {{\nbreak;\n}}\n{}\n}}",
+            hoisted.join("\n"),
+            cond,
+            body
         )
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ForLoop {
-    pub arg: Arg,
+    pub pattern: Pattern,
+    /// `None` if the type should be inferred from `iter`
+    pub ty: Option<Type>,
     pub iter: Expression,
     pub body: Vec<BlockPart>,
 }
 
 impl Parse for ForLoop {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        let mut arg = None;
+        let mut pattern = None;
+        let mut ty = None;
         let mut iter = None;
         let mut body = vec![];
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
-                Rule::define_argument => arg = Some(Arg::parse(rule)?),
+                Rule::define_argument => {
+                    let arg = Arg::parse(rule)?;
+
+                    pattern = Some(Pattern::Ident {
+                        ident: arg.ident,
+                        mutable: false,
+                    });
+                    ty = Some(arg.ty);
+                }
+                Rule::pattern => pattern = Some(Pattern::parse(rule)?),
                 Rule::expr => iter = Some(Expression::parse(rule)?),
                 Rule::block => body = BlockPart::parse_many(rule)?,
                 _ => {}
@@ -56,7 +77,8 @@ impl Parse for ForLoop {
         }
 
         Some(ForLoop {
-            arg: arg?,
+            pattern: pattern?,
+            ty,
             iter: iter?,
             body,
         })
@@ -65,11 +87,41 @@ impl Parse for ForLoop {
 
 impl ForLoop {
     pub fn rewrite(&self) -> String {
+        let ty = self
+            .ty
+            .clone()
+            .or_else(|| self.iter.infer_type()?.iter_item());
+
+        rewrite::scope::push();
+
+        // destructuring patterns bind from a temporary at the top of the body
+        let (ident, bindings) = match &self.pattern {
+            Pattern::Ident { ident, .. } => {
+                if let Some(ty) = &ty {
+                    rewrite::scope::declare(ident, ty.clone());
+                }
+
+                (rewrite_ident(ident), vec![])
+            }
+            pattern => {
+                let temp = rewrite::temp_ident();
+                let bindings = pattern.rewrite_bindings(&temp, ty.clone());
+
+                (temp, bindings)
+            }
+        };
+
+        let body = BlockPart::rewrite_many(self.body.clone(), "\n");
+
+        rewrite::scope::pop();
+
         format!(
-            "for ({} : {}) {{\n{}\n}}",
-            self.arg.rewrite(),
+            "for ({} {} : {}) {{\n{}{}\n}}",
+            ty.map(|ty| ty.rewrite()).unwrap_or("var".to_string()),
+            ident,
             self.iter.rewrite(),
-            BlockPart::rewrite_many(self.body.clone(), "\n")
+            bindings.iter().map(|n| format!("{}\n", n)).join(""),
+            body
         )
     }
 }
@@ -1,123 +1,865 @@
 use super::*;
 
+/// A literal in a pattern, e.g. `-1`, `'a'` or `"quit"`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchLiteral {
+    pub value: Definition,
+}
+
+impl Parse for MatchLiteral {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        Some(MatchLiteral {
+            value: Definition::parse_kind(pair.into_inner().next()?)?,
+        })
+    }
+}
+
+impl MatchLiteral {
+    /// As written in jasmine, for error messages
+    pub fn describe(&self) -> String {
+        match &self.value.kind {
+            DefinitionType::Float(FloatLiteral { text, .. }) => text.clone(),
+            DefinitionType::Int(IntLiteral { text, .. }) => text.clone(),
+            _ => self.value.rewrite(),
+        }
+    }
+
+    pub fn rewrite(&self) -> String {
+        self.value.rewrite()
+    }
+}
+
+/// A bound of a range pattern, e.g. `0` or `MAX` in `0..=MAX`
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatchBound {
+    Literal(MatchLiteral),
+    Const(String),
+}
+
+impl Parse for MatchBound {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        match pair.clone().into_inner().next()?.as_rule() {
+            Rule::ident => Some(MatchBound::Const(pair.as_str().trim().to_string())),
+            _ => Some(MatchBound::Literal(MatchLiteral::parse(pair)?)),
+        }
+    }
+}
+
+impl MatchBound {
+    pub fn describe(&self) -> String {
+        match self {
+            MatchBound::Literal(literal) => literal.describe(),
+            MatchBound::Const(ident) => ident.clone(),
+        }
+    }
+
+    pub fn rewrite(&self) -> String {
+        match self {
+            MatchBound::Literal(literal) => literal.rewrite(),
+            MatchBound::Const(ident) => {
+                if rewrite::scope::find_const(ident).is_none() {
+                    rewrite::report(format!(
+                        "Range pattern bounds must be literals or constants, found `{}`",
+                        ident
+                    ));
+                }
+
+                rewrite_ident(ident)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatchPattern {
+    Wildcard,
+    /// `x`, `x: ty` or `x @ pattern`
+    Binding {
+        ident: String,
+        ty: Option<Type>,
+        pattern: Option<Box<MatchPattern>>,
+    },
+    Literal(MatchLiteral),
+    Range {
+        start: MatchBound,
+        end: MatchBound,
+        inclusive: bool,
+    },
+    Variant {
+        ty_ident: String,
+        variant_ident: String,
+        data: Option<Box<MatchPattern>>,
+    },
+    Or(Vec<MatchPattern>),
+}
+
+impl Parse for MatchPattern {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        match pair.as_rule() {
+            Rule::match_pattern => {
+                let mut alternatives = pair
+                    .into_inner()
+                    .filter(|n| !matches!(n.as_rule(), Rule::lparen | Rule::rparen))
+                    .map(MatchPattern::parse)
+                    .collect::<Option<Vec<_>>>()?;
+
+                if alternatives.len() == 1 {
+                    alternatives.pop()
+                } else {
+                    Some(MatchPattern::Or(alternatives))
+                }
+            }
+            Rule::match_wildcard => Some(MatchPattern::Wildcard),
+            Rule::match_ident => Some(MatchPattern::Binding {
+                ident: pair.as_str().trim().to_string(),
+                ty: None,
+                pattern: None,
+            }),
+            Rule::match_typed => {
+                let mut ident = None;
+                let mut ty = None;
+
+                for rule in pair.into_inner() {
+                    match rule.as_rule() {
+                        Rule::ident => ident = Some(rule.as_str().to_string()),
+                        Rule::ty => ty = Some(Type::parse(rule)?),
+                        _ => {}
+                    }
+                }
+
+                Some(MatchPattern::Binding {
+                    ident: ident?,
+                    ty,
+                    pattern: None,
+                })
+            }
+            Rule::match_binding => {
+                let mut inner = pair.into_inner();
+
+                Some(MatchPattern::Binding {
+                    ident: inner.next()?.as_str().to_string(),
+                    ty: None,
+                    pattern: Some(Box::new(MatchPattern::parse(
+                        inner.find(|n| !matches!(n.as_rule(), Rule::lparen | Rule::rparen))?,
+                    )?)),
+                })
+            }
+            Rule::match_literal => Some(MatchPattern::Literal(MatchLiteral::parse(pair)?)),
+            Rule::match_range => {
+                let mut bounds = vec![];
+                let mut inclusive = false;
+
+                for rule in pair.into_inner() {
+                    match rule.as_rule() {
+                        Rule::match_bound => bounds.push(MatchBound::parse(rule)?),
+                        Rule::range_incl => inclusive = true,
+                        _ => {}
+                    }
+                }
+
+                let end = bounds.pop()?;
+
+                Some(MatchPattern::Range {
+                    start: bounds.pop()?,
+                    end,
+                    inclusive,
+                })
+            }
+            Rule::match_variant => {
+                let mut ty_ident = None;
+                let mut variant_ident = None;
+                let mut data = None;
+
+                for rule in pair.into_inner() {
+                    match rule.as_rule() {
+                        Rule::ident if ty_ident.is_none() => {
+                            ty_ident = Some(rule.as_str().to_string())
+                        }
+                        Rule::ident => variant_ident = Some(rule.as_str().to_string()),
+                        Rule::match_typed | Rule::match_pattern => {
+                            data = Some(Box::new(MatchPattern::parse(rule)?))
+                        }
+                        _ => {}
+                    }
+                }
+
+                Some(MatchPattern::Variant {
+                    ty_ident: ty_ident?,
+                    variant_ident: variant_ident?,
+                    data,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl MatchPattern {
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            MatchPattern::Wildcard => true,
+            MatchPattern::Binding { pattern, .. } => {
+                pattern.as_ref().is_none_or(|p| p.is_irrefutable())
+            }
+            MatchPattern::Or(alternatives) => alternatives.iter().any(|p| p.is_irrefutable()),
+            _ => false,
+        }
+    }
+
+    /// The patterns this one is made of, looking through `|` and `x @`
+    fn alternatives(&self) -> Vec<&MatchPattern> {
+        match self {
+            MatchPattern::Binding {
+                pattern: Some(pattern),
+                ..
+            } => pattern.alternatives(),
+            MatchPattern::Or(alternatives) => {
+                alternatives.iter().flat_map(|p| p.alternatives()).collect()
+            }
+            _ => vec![self],
+        }
+    }
+
+    /// As written in jasmine, for error messages
+    pub fn describe(&self) -> String {
+        match self {
+            MatchPattern::Wildcard => "_".to_string(),
+            MatchPattern::Binding {
+                ident,
+                pattern: Some(pattern),
+                ..
+            } => format!("{} @ {}", ident, pattern.describe()),
+            MatchPattern::Binding { ident, .. } => ident.clone(),
+            MatchPattern::Literal(literal) => literal.describe(),
+            MatchPattern::Range {
+                start,
+                end,
+                inclusive,
+            } => format!(
+                "{}..{}{}",
+                start.describe(),
+                if *inclusive { "=" } else { "" },
+                end.describe()
+            ),
+            MatchPattern::Variant {
+                ty_ident,
+                variant_ident,
+                data,
+            } => match data {
+                Some(data) => format!("{}::{}({})", ty_ident, variant_ident, data.describe()),
+                None => format!("{}::{}", ty_ident, variant_ident),
+            },
+            MatchPattern::Or(alternatives) => alternatives.iter().map(|p| p.describe()).join(" | "),
+        }
+    }
+
+    /// Java conditions that all hold when `source` matches this pattern.
+    /// Empty if the pattern always matches.
+    pub fn rewrite_conds(&self, source: &str) -> Vec<String> {
+        match self {
+            MatchPattern::Wildcard => vec![],
+            MatchPattern::Binding { pattern, .. } => pattern
+                .as_ref()
+                .map(|p| p.rewrite_conds(source))
+                .unwrap_or_default(),
+            MatchPattern::Literal(literal) => match literal.value.kind {
+                DefinitionType::String(_) => {
+                    vec![format!("{}.equals({})", source, literal.rewrite())]
+                }
+                _ => vec![format!("{} == {}", source, literal.rewrite())],
+            },
+            MatchPattern::Range {
+                start,
+                end,
+                inclusive,
+            } => vec![
+                format!("{} >= {}", source, start.rewrite()),
+                format!(
+                    "{} {} {}",
+                    source,
+                    if *inclusive { "<=" } else { "<" },
+                    end.rewrite()
+                ),
+            ],
+            MatchPattern::Variant {
+                ty_ident,
+                variant_ident,
+                data,
+            } => {
+                let mut conds = vec![format!(
+                    "{}.is({}._{})",
+                    source,
+                    rewrite_ident(ty_ident),
+                    rewrite_ident(variant_ident)
+                )];
+
+                if let Some(data) = data {
+                    conds.extend(data.rewrite_conds(&format!(
+                        "{}._getData_{}()",
+                        source,
+                        rewrite_ident(variant_ident)
+                    )));
+                }
+
+                conds
+            }
+            MatchPattern::Or(alternatives) => {
+                if alternatives
+                    .iter()
+                    .any(|p| !p.bindings(source, None).is_empty())
+                {
+                    rewrite::report("Bindings are not supported inside `|` patterns");
+                }
+
+                let alternatives = alternatives
+                    .iter()
+                    .map(|p| p.rewrite_conds(source))
+                    .collect_vec();
+
+                if alternatives.iter().any(|conds| conds.is_empty()) {
+                    return vec![];
+                }
+
+                vec![format!(
+                    "({})",
+                    alternatives
+                        .iter()
+                        .map(|conds| format!("({})", conds.join(" && ")))
+                        .join(" || ")
+                )]
+            }
+        }
+    }
+
+    /// The idents this pattern binds, with the java expression each one is read from
+    pub fn bindings(&self, source: &str, ty: Option<Type>) -> Vec<(String, String, Option<Type>)> {
+        match self {
+            MatchPattern::Binding {
+                ident,
+                ty: binding_ty,
+                pattern,
+            } => {
+                let mut bindings = vec![(
+                    ident.clone(),
+                    source.to_string(),
+                    binding_ty.clone().or(ty.clone()),
+                )];
+
+                if let Some(pattern) = pattern {
+                    bindings.extend(pattern.bindings(source, ty));
+                }
+
+                bindings
+            }
+            MatchPattern::Variant {
+                variant_ident,
+                data: Some(data),
+                ..
+            } => data.bindings(
+                &format!("{}._getData_{}()", source, rewrite_ident(variant_ident)),
+                ty.and_then(|ty| ty.variant_data(variant_ident)),
+            ),
+            _ => vec![],
+        }
+    }
+}
+
+/// The variants of an enum, and whether each one carries data
+fn enum_variants(ty_ident: &str) -> Option<Vec<(String, bool)>> {
+    match ty_ident {
+        "Option" => Some(vec![
+            ("Some".to_string(), true),
+            ("None".to_string(), false),
+        ]),
+        "Result" => Some(vec![("Ok".to_string(), true), ("Err".to_string(), true)]),
+        _ => Some(
+            rewrite::scope::find_enum(ty_ident)?
+                .variants
+                .into_iter()
+                .map(|v| (v.ident, v.data.is_some()))
+                .collect(),
+        ),
+    }
+}
+
+/// The values matched by `pattern` that none of `rows` match, described as patterns.
+/// Empty if `rows` cover `pattern` completely.
+fn uncovered(rows: &[&MatchPattern], pattern: &MatchPattern) -> Vec<String> {
+    let rows = rows.iter().flat_map(|r| r.alternatives()).collect_vec();
+
+    if rows.iter().any(|r| r.is_irrefutable()) {
+        return vec![];
+    }
+
+    match pattern {
+        MatchPattern::Or(alternatives) => alternatives
+            .iter()
+            .flat_map(|p| uncovered(&rows, p))
+            .collect(),
+        MatchPattern::Binding {
+            pattern: Some(pattern),
+            ..
+        } => uncovered(&rows, pattern),
+        MatchPattern::Wildcard | MatchPattern::Binding { pattern: None, .. } => {
+            // only enums can be covered without a catch-all
+            let variants = rows.iter().find_map(|r| match r {
+                MatchPattern::Variant { ty_ident, .. } => {
+                    Some((ty_ident.clone(), enum_variants(ty_ident)?))
+                }
+                _ => None,
+            });
+
+            let Some((ty_ident, variants)) = variants else {
+                return vec!["_".to_string()];
+            };
+
+            variants
+                .into_iter()
+                .flat_map(|(variant_ident, _)| {
+                    uncovered(
+                        &rows,
+                        &MatchPattern::Variant {
+                            ty_ident: ty_ident.clone(),
+                            variant_ident,
+                            data: None,
+                        },
+                    )
+                })
+                .collect()
+        }
+        MatchPattern::Variant {
+            ty_ident,
+            variant_ident,
+            data,
+        } => {
+            let has_data = enum_variants(ty_ident)
+                .and_then(|variants| variants.into_iter().find(|(v, _)| v == variant_ident))
+                .is_some_and(|(_, has_data)| has_data);
+
+            let mut data_rows = vec![];
+
+            for row in rows.iter() {
+                match row {
+                    MatchPattern::Variant {
+                        variant_ident: row_variant,
+                        data: row_data,
+                        ..
+                    } if row_variant == variant_ident => match row_data {
+                        Some(row_data) => data_rows.push(row_data.as_ref()),
+                        None => return vec![],
+                    },
+                    _ => {}
+                }
+            }
+
+            if !has_data {
+                return match data_rows.is_empty() {
+                    true => vec![format!("{}::{}", ty_ident, variant_ident)],
+                    false => vec![],
+                };
+            }
+
+            let data = data.as_deref().unwrap_or(&MatchPattern::Wildcard);
+
+            uncovered(&data_rows, data)
+                .into_iter()
+                .map(|missing| format!("{}::{}({})", ty_ident, variant_ident, missing))
+                .collect()
+        }
+        _ => match rows.contains(&pattern) {
+            true => vec![],
+            false => vec![pattern.describe()],
+        },
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct MatchArm {
-    pub ty_ident: String,
-    pub variant_ident: String,
-    pub data_ident: Option<String>,
-    pub data_ident_ty: Option<Type>,
+    pub pattern: MatchPattern,
+    pub guard: Option<Expression>,
     pub body: Vec<BlockPart>,
+    /// `Some` if the match is used as a value
+    pub value: Option<ValueBlock>,
 }
 
 impl Parse for MatchArm {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        let mut ty_ident = None;
-        let mut variant_ident = None;
-        let mut after_dblcln = false;
-
-        let mut data_ident = None;
+        let mut pattern = None;
+        let mut guard = None;
         let mut body = vec![];
-        let mut ty = None;
+        let mut value = None;
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
-                Rule::ident if !after_dblcln => ty_ident = Some(rule.as_str().to_string()),
-                Rule::ident if after_dblcln && variant_ident.is_none() => {
-                    variant_ident = Some(rule.as_str().to_string())
-                }
-                Rule::ident if after_dblcln && variant_ident.is_some() => {
-                    data_ident = Some(rule.as_str().to_string())
+                Rule::match_pattern => pattern = Some(MatchPattern::parse(rule)?),
+                Rule::value_block | Rule::expr => value = Some(ValueBlock::parse(rule)?),
+                Rule::match_guard => {
+                    guard = Some(Expression::parse(
+                        rule.into_inner().find(|n| n.as_rule() == Rule::expr)?,
+                    )?)
                 }
                 Rule::block => body = BlockPart::parse_many(rule)?,
-                Rule::dblcln => after_dblcln = true,
-                Rule::ty => ty = Some(Type::parse(rule)?),
                 _ => {}
             }
         }
 
         Some(MatchArm {
-            ty_ident: ty_ident?,
-            variant_ident: variant_ident?,
-            data_ident,
-            data_ident_ty: ty,
+            pattern: pattern?,
+            guard,
             body,
+            value,
         })
     }
 }
 
+impl MatchArm {
+    /// `None` if this arm always matches
+    fn rewrite_cond(
+        &self,
+        source: &str,
+        bindings: &[(String, String, Option<Type>)],
+    ) -> Option<String> {
+        let mut conds = self.pattern.rewrite_conds(source);
+
+        if let Some(guard) = &self.guard {
+            // bindings aren't declared yet, so the guard reads them from the source
+            conds.push(format!(
+                "({})",
+                Self::with_inline_bindings(bindings, || guard.rewrite())
+            ));
+        }
+
+        (!conds.is_empty()).then(|| conds.join(" && "))
+    }
+
+    /// Run `f` with `bindings` readable straight from their sources, without declaring them
+    fn with_inline_bindings<T>(
+        bindings: &[(String, String, Option<Type>)],
+        f: impl FnOnce() -> T,
+    ) -> T {
+        rewrite::scope::push();
+
+        for (ident, _, ty) in bindings {
+            if let Some(ty) = ty {
+                rewrite::scope::declare(ident, ty.clone());
+            }
+        }
+
+        let aliases = bindings
+            .iter()
+            .map(|(ident, source, _)| (ident.clone(), format!("({})", source)))
+            .collect_vec();
+
+        let result = rewrite::scope::with_aliases(&aliases, f);
+
+        rewrite::scope::pop();
+
+        result
+    }
+
+    /// The bindings, then the body. If `target` is given the arm's value is assigned to it.
+    fn rewrite_body(
+        &self,
+        bindings: &[(String, String, Option<Type>)],
+        target: Option<&str>,
+    ) -> String {
+        rewrite::scope::push();
+
+        let mut rewritten = "".to_string();
+
+        for (ident, source, ty) in bindings {
+            if let Some(ty) = ty {
+                rewrite::scope::declare(ident, ty.clone());
+            }
+
+            rewritten.push_str(&format!(
+                "{} {} = {};\n",
+                ty.as_ref()
+                    .map(|ty| ty.rewrite())
+                    .unwrap_or("var".to_string()),
+                rewrite_ident(ident),
+                source
+            ));
+        }
+
+        match (target, &self.value) {
+            (Some(target), Some(value)) => rewritten.push_str(&value.rewrite_assign(target)),
+            _ => rewritten.push_str(&BlockPart::rewrite_many(self.body.clone(), "\n")),
+        }
+
+        rewrite::scope::pop();
+
+        rewritten
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct MatchStatement {
     pub expr: Expression,
     pub arms: Vec<MatchArm>,
-    pub final_arm: Option<Vec<BlockPart>>,
 }
-
 impl Parse for MatchStatement {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut expr = None;
         let mut arms = vec![];
-        let mut final_arm = None;
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
                 Rule::expr => expr = Some(Expression::parse(rule)?),
-                Rule::match_arm => arms.push(MatchArm::parse(rule)?),
-                Rule::block => final_arm = Some(BlockPart::parse_many(rule)?),
+                Rule::match_arm | Rule::match_value_arm => arms.push(MatchArm::parse(rule)?),
                 _ => {}
             }
         }
 
-        Some(MatchStatement {
-            expr: expr?,
-            arms,
-            final_arm,
-        })
+        Some(MatchStatement { expr: expr?, arms })
     }
 }
 
 impl MatchStatement {
     pub fn rewrite(&self) -> String {
-        let arms_iter = self.arms.iter();
-        let mut rewritten = "".to_string();
+        // evaluate the matched expression only once
+        let source = rewrite::temp_ident();
+        let mut rewritten = format!("final var {} = {};\n", source, self.expr.rewrite());
 
-        for arm in arms_iter {
-            let data_sect = 'block: {
-                let Some(ident) = &arm.data_ident else { break 'block String::from("") };
-                let Some(ty) = &arm.data_ident_ty else { break 'block String::from("") };
+        rewritten.push_str(&self.rewrite_arms(&source, None));
 
-                format!(
-                    "{} {} = {}._getData_{}();\n",
-                    ty.rewrite(),
-                    rewrite_ident(ident),
-                    self.expr.rewrite(),
-                    rewrite_ident(&arm.variant_ident)
-                )
-            };
+        rewritten
+    }
 
-            rewritten.push_str(&format!(
-                "else if ({}.is({}.{})) {{
-					{}{}
-				}} ",
-                self.expr.rewrite(),
-                rewrite_ident(&arm.ty_ident),
-                format!("_{}", rewrite_ident(&arm.variant_ident)),
-                data_sect,
-                BlockPart::rewrite_many(arm.body.clone(), "\n")
+    /// Report unreachable arms, and variants missing from a match on an enum.
+    /// `true` if the arms without guards cover every value.
+    fn check_exhaustive(&self) -> bool {
+        let mut rows = vec![];
+
+        for arm in self.arms.iter() {
+            if uncovered(&rows, &arm.pattern).is_empty() {
+                rewrite::report(format!(
+                    "Unreachable match arm `{}`, it is covered by the arms before it",
+                    arm.pattern.describe()
+                ));
+            }
+
+            if arm.guard.is_none() {
+                rows.push(&arm.pattern);
+            }
+        }
+
+        let missing = uncovered(&rows, &MatchPattern::Wildcard);
+
+        let is_enum = rows
+            .iter()
+            .flat_map(|r| r.alternatives())
+            .any(|r| matches!(r, MatchPattern::Variant { .. }));
+
+        if is_enum && !missing.is_empty() {
+            rewrite::report(format!(
+                "Match is not exhaustive, missing {}",
+                missing.iter().map(|m| format!("`{}`", m)).join(", ")
             ));
         }
 
-        rewritten.push_str(&format!(
-            "else {{
-				{}
-			}} ",
-            self.final_arm
-                .as_ref()
-                .map(|a| BlockPart::rewrite_many(a.to_vec(), "\n"))
-                .unwrap_or(
-                    "throw new RuntimeException(\"Not all match arms were covered in this statement\");".to_string()
-                )
+        missing.is_empty()
+    }
+
+    /// The condition for each arm, `None` where it doesn't need checking
+    fn arm_cond(
+        &self,
+        idx: usize,
+        exhaustive: bool,
+        source: &str,
+        bindings: &[(String, String, Option<Type>)],
+    ) -> Option<String> {
+        let arm = &self.arms[idx];
+        let cond = arm.rewrite_cond(source, bindings);
+
+        // every value left over matches the last arm
+        let is_last = idx + 1 == self.arms.len();
+
+        if exhaustive && is_last && arm.guard.is_none() {
+            return None;
+        }
+
+        cond
+    }
+
+    /// The if-chain over `source`, assigning each arm's value to `target` if given
+    fn rewrite_arms(&self, source: &str, target: Option<&str>) -> String {
+        let ty = self.expr.infer_type();
+        let exhaustive = self.check_exhaustive();
+
+        let mut branches = vec![];
+        let mut fallback = None;
+
+        for (idx, arm) in self.arms.iter().enumerate() {
+            let bindings = arm.pattern.bindings(source, ty.clone());
+            let (hoisted, cond) =
+                rewrite::collect_hoisted(|| self.arm_cond(idx, exhaustive, source, &bindings));
+            let body = arm.rewrite_body(&bindings, target);
+
+            match cond {
+                Some(cond) => branches.push((hoisted, cond, body)),
+                None => {
+                    fallback = Some(body);
+                    break;
+                }
+            }
+        }
+
+        rewrite::rewrite_if_chain(
+            branches,
+            Some(fallback.unwrap_or(
+                "throw new RuntimeException(\"Not all match arms were covered in this statement\");"
+                    .to_string(),
+            )),
+        )
+    }
+
+    /// The type of the value of the first arm it can be inferred for
+    pub fn infer_type(&self) -> Option<Type> {
+        let ty = self.expr.infer_type();
+
+        self.arms.iter().find_map(|arm| {
+            let bindings = arm.pattern.bindings("", ty.clone());
+
+            MatchArm::with_inline_bindings(&bindings, || arm.value.as_ref()?.infer_type())
+        })
+    }
+
+    /// A match used as a value. This is a chain of ternaries if every arm is a lone expression
+    /// and the matched expression is a plain variable, otherwise a temporary assigned in each arm.
+    pub fn rewrite_value(&self, ty: Option<&Type>) -> String {
+        let is_plain_variable = matches!(
+            &self.expr,
+            Expression::Base(BaseExpr { operators, kind })
+                if operators.is_empty()
+                    && matches!(
+                        kind.as_ref(),
+                        BaseExprType::Ident {
+                            static_fn: None,
+                            unit_enum: None,
+                            after_dot: None,
+                            ..
+                        }
+                    )
+        );
+
+        let is_ternary = is_plain_variable
+            && self
+                .arms
+                .iter()
+                .all(|arm| arm.value.as_ref().is_some_and(|v| v.body.is_empty()));
+
+        if is_ternary {
+            return self.rewrite_ternary(ty);
+        }
+
+        let Some((ty, target)) = self.declare_target(ty) else {
+            return "null".to_string();
+        };
+
+        let source = rewrite::temp_ident();
+
+        rewrite::hoist(format!(
+            "final var {} = {};\n{} {};\n{}",
+            source,
+            self.expr.rewrite(),
+            ty.rewrite(),
+            target,
+            self.rewrite_arms(&source, Some(&target))
         ));
 
-        rewritten.split("else").skip(1).join("else")
+        target
+    }
+
+    /// The arms as nested ternaries over the matched variable. Statements hoisted out of a value,
+    /// or out of any guard but the first, must only run when their arm is reached,
+    /// so then the arms assign a temporary instead.
+    fn rewrite_ternary(&self, ty: Option<&Type>) -> String {
+        let source = self.expr.rewrite();
+        let scrutinee_ty = self.expr.infer_type();
+        let exhaustive = self.check_exhaustive();
+
+        let mut branches = vec![];
+        let mut fallback = (vec![], "_matchFailed_()".to_string());
+
+        for (idx, arm) in self.arms.iter().enumerate() {
+            let bindings = arm.pattern.bindings(&source, scrutinee_ty.clone());
+            let (hoisted, cond) =
+                rewrite::collect_hoisted(|| self.arm_cond(idx, exhaustive, &source, &bindings));
+            let value = MatchArm::with_inline_bindings(&bindings, || {
+                rewrite::collect_hoisted(|| {
+                    arm.value
+                        .as_ref()
+                        .map(|v| v.value.rewrite())
+                        .unwrap_or_default()
+                })
+            });
+
+            match cond {
+                Some(cond) => branches.push((hoisted, cond, value)),
+                None => {
+                    fallback = value;
+                    break;
+                }
+            }
+        }
+
+        let is_lazy = !fallback.0.is_empty()
+            || branches
+                .iter()
+                .enumerate()
+                .any(|(idx, (hoisted, _, value))| {
+                    (idx > 0 && !hoisted.is_empty()) || !value.0.is_empty()
+                });
+
+        if !is_lazy {
+            for (hoisted, _, _) in branches.iter_mut() {
+                hoisted.drain(..).for_each(rewrite::hoist);
+            }
+
+            return branches
+                .into_iter()
+                .rev()
+                .fold(fallback.1, |rest, (_, cond, (_, value))| {
+                    format!("({} ? {} : {})", cond, value, rest)
+                });
+        }
+
+        let Some((ty, target)) = self.declare_target(ty) else {
+            return "null".to_string();
+        };
+
+        let assign = |(hoisted, value): (Vec<String>, String)| {
+            hoisted
+                .into_iter()
+                .chain(std::iter::once(format!("{} = {};", target, value)))
+                .join("\n")
+        };
+
+        let branches = branches
+            .into_iter()
+            .map(|(hoisted, cond, value)| (hoisted, cond, assign(value)))
+            .collect_vec();
+
+        rewrite::hoist(format!(
+            "{} {};\n{}",
+            ty.rewrite(),
+            target,
+            rewrite::rewrite_if_chain(branches, Some(assign(fallback)))
+        ));
+
+        target
+    }
+
+    /// The type of the value and a fresh temporary to assign it to
+    fn declare_target(&self, ty: Option<&Type>) -> Option<(Type, String)> {
+        let Some(ty) = ty.cloned().or_else(|| self.infer_type()) else {
+            rewrite::report(
+                "Cannot infer the type of this `match` expression, add a type annotation",
+            );
+            return None;
+        };
+
+        Some((ty, rewrite::temp_ident()))
     }
 }
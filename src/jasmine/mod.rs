@@ -1,8 +1,11 @@
 pub mod arguments;
 pub mod blocks;
+pub mod casts;
 pub mod chars;
 pub mod conditionals;
+pub mod constants;
 pub mod definitions;
+pub mod derives;
 pub mod enums;
 pub mod expressions;
 pub mod functions;
@@ -10,17 +13,24 @@ pub mod generics;
 pub mod impls;
 pub mod loops;
 pub mod matches;
+pub mod modules;
 pub mod oop;
 pub mod operators;
+pub mod patterns;
 pub mod statements;
+pub mod traits;
 pub mod types;
+pub mod visibility;
 
 pub use crate::prelude::*;
 pub use arguments::*;
 pub use blocks::*;
+pub use casts::*;
 pub use chars::*;
 pub use conditionals::*;
+pub use constants::*;
 pub use definitions::*;
+pub use derives::*;
 pub use enums::*;
 pub use expressions::*;
 pub use functions::*;
@@ -28,10 +38,14 @@ pub use generics::*;
 pub use impls::*;
 pub use loops::*;
 pub use matches::*;
+pub use modules::*;
 pub use oop::*;
 pub use operators::*;
+pub use patterns::*;
 pub use statements::*;
+pub use traits::*;
 pub use types::*;
+pub use visibility::*;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum JasmineProgramComponent {
@@ -39,7 +53,12 @@ pub enum JasmineProgramComponent {
     Impl(Impl),
     Fn(Function),
     Var(Variable),
+    Const(Constant),
+    Static(Static),
     Enum(Enumeration),
+    Trait(Trait),
+    Mod(Module),
+    Use(Use),
 }
 
 impl Parse for JasmineProgramComponent {
@@ -49,22 +68,64 @@ impl Parse for JasmineProgramComponent {
             Rule::impl_def => Some(Self::Impl(Impl::parse(pair)?)),
             Rule::fn_def => Some(Self::Fn(Function::parse(pair)?)),
             Rule::var => Some(Self::Var(Variable::parse(pair)?)),
+            Rule::const_def => Some(Self::Const(Constant::parse(pair)?)),
+            Rule::static_def => Some(Self::Static(Static::parse(pair)?)),
             Rule::enum_def => Some(Self::Enum(Enumeration::parse(pair)?)),
+            Rule::trait_def => Some(Self::Trait(Trait::parse(pair)?)),
+            Rule::mod_def => Some(Self::Mod(Module::parse(pair)?)),
+            Rule::use_def => Some(Self::Use(Use::parse(pair)?)),
             _ => None,
         }
     }
 }
 
+impl JasmineProgramComponent {
+    /// The name this component defines at the top level, if any
+    pub fn item_ident(&self) -> Option<&String> {
+        match self {
+            Self::Struct(s) => Some(&s.ident),
+            Self::Fn(f) => Some(&f.ident),
+            Self::Var(v) => Some(&v.ident),
+            Self::Static(s) => Some(&s.var.ident),
+            Self::Const(c) => Some(&c.ident),
+            Self::Enum(e) => Some(&e.ident),
+            Self::Trait(t) => Some(&t.ident),
+            Self::Mod(m) => Some(&m.ident),
+            Self::Impl(_) | Self::Use(_) => None,
+        }
+    }
+
+    /// Whether other modules can import this, private items are only visible to their module and its children
+    pub fn is_private(&self) -> bool {
+        let visibility = match self {
+            Self::Struct(s) => &s.visibility,
+            Self::Fn(f) => &f.visibility,
+            Self::Const(c) => &c.visibility,
+            Self::Static(s) => &s.visibility,
+            Self::Enum(e) => &e.visibility,
+            Self::Trait(t) => &t.visibility,
+            _ => return false,
+        };
+
+        *visibility == Visibility::Private
+    }
+}
+
 impl ParseMany for JasmineProgramComponent {
     fn parse_many(pair: Pair<'_, Rule>) -> Option<Vec<Self>> {
         let mut components = vec![];
         for inner_pair in pair.into_inner() {
-            if !vec![
+            if ![
                 Rule::struct_def,
                 Rule::impl_def,
                 Rule::fn_def,
                 Rule::var,
+                Rule::const_def,
+                Rule::static_def,
                 Rule::enum_def,
+                Rule::trait_def,
+                Rule::mod_def,
+                Rule::use_def,
             ]
             .contains(&inner_pair.as_rule())
             {
@@ -0,0 +1,315 @@
+use super::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    pub ident: String,
+}
+
+impl Parse for Module {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut ident = None;
+
+        for rule in pair.into_inner() {
+            if rule.as_rule() == Rule::ident {
+                ident = Some(rule.as_str().to_string());
+            }
+        }
+
+        Some(Self { ident: ident? })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UseKind {
+    /// `use a::b::Item;`
+    Single,
+    /// `use a::b::*;`
+    Glob,
+    /// `use a::b::{Item, Other};`
+    Group(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Use {
+    pub path: Vec<String>,
+    pub kind: UseKind,
+}
+
+impl Parse for Use {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut path = vec![];
+        let mut kind = UseKind::Single;
+
+        for rule in pair.into_inner() {
+            match rule.as_rule() {
+                Rule::ident => path.push(rule.as_str().to_string()),
+                Rule::use_glob => kind = UseKind::Glob,
+                Rule::use_group => {
+                    kind = UseKind::Group(
+                        rule.into_inner()
+                            .filter(|n| n.as_rule() == Rule::ident)
+                            .map(|n| n.as_str().to_string())
+                            .collect(),
+                    )
+                }
+                _ => {}
+            }
+        }
+
+        Some(Self { path, kind })
+    }
+}
+
+impl Use {
+    /// The module path this `use` imports from, and the names it imports
+    pub fn split(&self) -> (Vec<String>, Vec<String>) {
+        match &self.kind {
+            UseKind::Single => {
+                let mut module = self.path.clone();
+                let item = module.pop().into_iter().collect();
+
+                (module, item)
+            }
+            UseKind::Glob => (self.path.clone(), vec![]),
+            UseKind::Group(items) => (self.path.clone(), items.clone()),
+        }
+    }
+}
+
+/// A file of the program, the module path (e.g. `["geometry", "shapes"]`) it was loaded as
+/// and the paths it names outside of expressions that may refer to locals
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgramModule {
+    pub path: Vec<String>,
+    pub components: Vec<JasmineProgramComponent>,
+    /// type names, struct literals, `a::b::item` paths and enum variants in patterns
+    pub paths: Vec<Vec<String>>,
+}
+
+impl ProgramModule {
+    /// The paths named by `pair`, see `ProgramModule::paths`
+    pub fn collect_paths(pair: Pair<'_, Rule>) -> Vec<Vec<String>> {
+        let idents = |pair: Pair<'_, Rule>| {
+            pair.into_inner()
+                .flatten()
+                .filter(|n| n.as_rule() == Rule::ident)
+                .map(|n| n.as_str().to_string())
+                .collect_vec()
+        };
+
+        let mut paths = vec![];
+
+        for pair in pair.into_inner().flatten() {
+            match pair.as_rule() {
+                Rule::ident_ty | Rule::r#struct => paths.push(
+                    pair.into_inner()
+                        .take_while(|n| n.as_rule() != Rule::lbrace)
+                        .filter(|n| n.as_rule() == Rule::ident)
+                        .map(|n| n.as_str().to_string())
+                        .collect(),
+                ),
+                Rule::match_variant => paths.push(idents(pair).into_iter().take(1).collect()),
+                Rule::base_expr => {
+                    let mut path = vec![];
+
+                    for rule in pair.into_inner() {
+                        match rule.as_rule() {
+                            Rule::ident => path.push(rule.as_str().to_string()),
+                            Rule::path_prefix => path.extend(idents(rule)),
+                            Rule::static_fn | Rule::unit_enum => {
+                                path.extend(idents(rule).into_iter().take(1));
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    // a lone ident may be a local, it is checked while rewriting
+                    if path.len() > 1 {
+                        paths.push(path);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        paths
+    }
+}
+
+/// `a::b` for a module path, and `crate` for the root
+pub fn display_path(path: &[String]) -> String {
+    if path.is_empty() {
+        "crate".to_string()
+    } else {
+        path.join("::")
+    }
+}
+
+/// Resolve a module path written in module `from` to an absolute module path
+pub fn resolve_module(
+    modules: &[ProgramModule],
+    from: &[String],
+    path: &[String],
+) -> Option<Vec<String>> {
+    resolve_declared(modules, from, path).or_else(|| {
+        let (first, rest) = path.split_first()?;
+
+        // `use geometry::shapes;` makes `shapes::area` name `geometry::shapes::area`
+        imports(modules, from)
+            .find(|(_, item)| item == first)
+            .map(|(module, item)| [&module[..], &[item], rest].concat())
+            .filter(|path| modules.iter().any(|m| &m.path == path))
+    })
+}
+
+/// `resolve_module` without the modules imported with `use`
+fn resolve_declared(
+    modules: &[ProgramModule],
+    from: &[String],
+    path: &[String],
+) -> Option<Vec<String>> {
+    let exists = |path: &Vec<String>| modules.iter().any(|m| &m.path == path);
+
+    let resolved = match path.first().map(|n| n.as_str()) {
+        Some("crate") => path[1..].to_vec(),
+        Some("self") => [from, &path[1..]].concat(),
+        Some("super") => {
+            let mut resolved = from.to_vec();
+            resolved.pop()?;
+            [&resolved[..], &path[1..]].concat()
+        }
+        _ => {
+            let relative = [from, path].concat();
+
+            if exists(&relative) {
+                relative
+            } else {
+                path.to_vec()
+            }
+        }
+    };
+
+    exists(&resolved).then_some(resolved)
+}
+
+/// The `(module, item)` pairs named by the `use`s of module `from`, a glob names every item
+/// it can see
+fn imports<'a>(
+    modules: &'a [ProgramModule],
+    from: &'a [String],
+) -> impl Iterator<Item = (Vec<String>, String)> + 'a {
+    modules
+        .iter()
+        .filter(move |m| m.path == from)
+        .flat_map(|m| m.components.iter())
+        .filter_map(|n| match n {
+            JasmineProgramComponent::Use(import) => Some(import),
+            _ => None,
+        })
+        .flat_map(move |import| {
+            let (module_path, items) = import.split();
+
+            let Some(target) = resolve_declared(modules, from, &module_path) else {
+                return vec![];
+            };
+
+            let items = match import.kind {
+                UseKind::Glob => modules
+                    .iter()
+                    .filter(|m| m.path == target)
+                    .flat_map(|m| m.components.iter())
+                    .filter(|n| !n.is_private() || from.starts_with(&target))
+                    .filter_map(|n| n.item_ident().cloned())
+                    .collect(),
+                _ => items,
+            };
+
+            items
+                .into_iter()
+                .map(|item| (target.clone(), item))
+                .collect_vec()
+        })
+}
+
+/// The component defining `ident` in `module`
+fn find_item<'a>(
+    modules: &'a [ProgramModule],
+    module: &[String],
+    ident: &str,
+) -> Option<&'a JasmineProgramComponent> {
+    modules
+        .iter()
+        .find(|m| m.path == module)?
+        .components
+        .iter()
+        .find(|n| n.item_ident().is_some_and(|n| n == ident))
+}
+
+/// The module defining the item `ident`, names other than modules are unique across modules
+pub fn module_of(modules: &[ProgramModule], ident: &str) -> Option<Vec<String>> {
+    modules
+        .iter()
+        .find(|m| find_item(modules, &m.path, ident).is_some())
+        .map(|m| m.path.clone())
+}
+
+/// The error if `path`, e.g. `["geometry", "shapes", "area"]` or `["Point"]`, does not name an
+/// item visible in module `from`. Names that are not items of the program, like `Vec` or a type
+/// parameter, are left alone.
+pub fn path_error(modules: &[ProgramModule], from: &[String], path: &[String]) -> Option<String> {
+    let (ident, module_path) = path.split_last()?;
+
+    if module_path.is_empty() {
+        let defined_in = module_of(modules, ident)?;
+
+        if defined_in == from
+            || find_item(modules, from, ident).is_some()
+            || imports(modules, from).any(|(_, item)| &item == ident)
+        {
+            return None;
+        }
+
+        if find_item(modules, &defined_in, ident).is_some_and(|n| n.is_private())
+            && !from.starts_with(&defined_in)
+        {
+            return Some(private_error(ident, &defined_in, from));
+        }
+
+        return Some(format!(
+            "Cannot find `{}` in `{}`, import it with `use {}::{};`",
+            ident,
+            display_path(from),
+            display_path(&defined_in),
+            ident
+        ));
+    }
+
+    let Some(target) = resolve_module(modules, from, module_path) else {
+        // `Type::member`, where `Type` must be visible
+        return path_error(modules, from, module_path);
+    };
+
+    let Some(component) = find_item(modules, &target, ident) else {
+        return Some(format!(
+            "Cannot find `{}` in `{}`",
+            ident,
+            display_path(&target)
+        ));
+    };
+
+    // a module sees the private items of its parents, like rust
+    if component.is_private() && !from.starts_with(&target) {
+        return Some(private_error(ident, &target, from));
+    }
+
+    None
+}
+
+fn private_error(ident: &str, module: &[String], from: &[String]) -> String {
+    format!(
+        "`{}` is private to `{}`, mark it `pub` to use it in `{}`",
+        ident,
+        display_path(module),
+        display_path(from)
+    )
+}
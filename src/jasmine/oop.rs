@@ -31,9 +31,8 @@ impl ParseMany for StructArg {
         let mut args = vec![];
 
         for rule in pair.into_inner() {
-            match rule.as_rule() {
-                Rule::struct_arg => args.push(Self::parse(rule)?),
-                _ => {}
+            if rule.as_rule() == Rule::struct_arg {
+                args.push(Self::parse(rule)?);
             }
         }
 
@@ -69,18 +68,84 @@ impl Parse for CreateStructure {
 
 impl CreateStructure {
     pub fn rewrite(&self) -> String {
+        let ident = rewrite::scope::resolve_self(&self.ident);
+        let structure = rewrite::scope::find_struct(&ident);
+
         let fields = self
             .fields
             .iter()
             .sorted_by(|a, b| a.ident.cmp(&b.ident))
-            .map(|n| &n.value)
-            .cloned()
-            .collect_vec();
+            .map(|n| {
+                let ty = structure
+                    .as_ref()
+                    .and_then(|s| s.fields.iter().find(|f| f.ident == n.ident))
+                    .map(|f| &f.ty);
+
+                n.value.rewrite_as(ty)
+            })
+            .join(", ");
+
+        // inside its own impl a generic struct uses the type parameters of the impl,
+        // elsewhere java infers them
+        let ty = match structure.and_then(|s| s.generics) {
+            Some(_) => rewrite::scope::lookup("self")
+                .filter(|ty| ty.generic_parts().is_some_and(|(outer, _)| outer == ident))
+                .map(|ty| ty.rewrite())
+                .unwrap_or(format!("{}<>", ident)),
+            None => ident,
+        };
+
+        format!("new {}({})", ty, fields)
+    }
+}
+
+/// A field of a struct definition, e.g. `pub x: int`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub visibility: Visibility,
+    pub ident: String,
+    pub ty: Type,
+}
+
+impl Parse for Field {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut visibility = Visibility::Private;
+        let mut ident = None;
+        let mut ty = None;
 
+        for rule in pair.into_inner() {
+            match rule.as_rule() {
+                Rule::visibility => visibility = Visibility::parse(rule)?,
+                Rule::ident => ident = Some(rule.as_str().to_string()),
+                Rule::ty => ty = Some(Type::parse(rule)?),
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            visibility,
+            ident: ident?,
+            ty: ty?,
+        })
+    }
+}
+
+impl ParseMany for Field {
+    fn parse_many(pair: Pair<'_, Rule>) -> Option<Vec<Self>> {
+        pair.into_inner()
+            .filter(|rule| rule.as_rule() == Rule::struct_field)
+            .map(Field::parse)
+            .collect()
+    }
+}
+
+impl Field {
+    pub fn rewrite(&self) -> String {
         format!(
-            "new {}({})",
-            self.ident,
-            Expression::rewrite_many(fields, ", ")
+            "{}{} {};",
+            self.visibility.rewrite(),
+            self.ty.rewrite(),
+            rewrite_ident(&self.ident)
         )
     }
 }
@@ -88,27 +153,31 @@ impl CreateStructure {
 #[derive(Clone, Debug, PartialEq)]
 pub struct Structure {
     pub ident: String,
-    pub fields: Vec<Arg>,
+    pub visibility: Visibility,
+    pub fields: Vec<Field>,
     pub generics: Option<GenericArguments>,
     pub where_clause: Option<Vec<WhereUnit>>,
+    /// the traits of `#[derive(...)]`
+    pub derives: Vec<String>,
 }
 
 impl Parse for Structure {
     fn parse(pair: Pair<'_, Rule>) -> Option<Structure> {
         let mut fields = vec![];
         let mut ident = None;
+        let mut visibility = Visibility::Private;
         let mut generics = None;
         let mut where_clause = None;
+        let mut derives = vec![];
 
         for struct_part in pair.into_inner() {
             match struct_part.as_rule() {
                 Rule::ident => {
                     ident = Some(struct_part.as_str().to_string());
                 }
-                Rule::define_arguments => {
-                    let args = Arg::parse_many(struct_part)?;
-
-                    fields = args;
+                Rule::visibility => visibility = Visibility::parse(struct_part)?,
+                Rule::struct_fields => {
+                    fields = Field::parse_many(struct_part)?;
                 }
                 Rule::generic_args => {
                     generics = Some(GenericArguments::parse(struct_part)?);
@@ -116,15 +185,18 @@ impl Parse for Structure {
                 Rule::where_clause => {
                     where_clause = Some(WhereUnit::parse_many(struct_part)?);
                 }
+                Rule::derive_attr => derives.extend(parse_derives(struct_part)),
                 _ => {}
             }
         }
 
         Some(Structure {
             ident: ident?,
+            visibility,
             fields,
             generics,
             where_clause,
+            derives,
         })
     }
 }
@@ -17,10 +17,14 @@ impl Parse for UnaryOperator {
 }
 
 impl UnaryOperator {
-    pub fn rewrite(&self) -> String {
+    /// `operand` is the type this is applied to, `!` on integers is bitwise
+    pub fn rewrite(&self, operand: Option<&Type>) -> String {
         match self {
             UnaryOperator::Neg => "-",
-            UnaryOperator::Not => "!",
+            UnaryOperator::Not => match operand {
+                Some(ty) if ty.is_integer() || ty.which == WhichType::Char => "~",
+                _ => "!",
+            },
         }
         .to_string()
     }
@@ -41,6 +45,11 @@ pub enum BinaryOperator {
     Gt,
     Lte,
     Gte,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
 }
 
 impl Parse for BinaryOperator {
@@ -59,11 +68,68 @@ impl Parse for BinaryOperator {
             Rule::gt_op => Some(BinaryOperator::Gt),
             Rule::lte_op => Some(BinaryOperator::Lte),
             Rule::gte_op => Some(BinaryOperator::Gte),
+            Rule::bit_and_op => Some(BinaryOperator::BitAnd),
+            Rule::bit_or_op => Some(BinaryOperator::BitOr),
+            Rule::bit_xor_op => Some(BinaryOperator::BitXor),
+            Rule::shl_op => Some(BinaryOperator::Shl),
+            Rule::shr_op => Some(BinaryOperator::Shr),
             _ => None,
         }
     }
 }
 
+impl BinaryOperator {
+    /// The trait that overloads this operator, e.g. `Add` for `a + b`
+    pub fn overload(&self) -> Option<&'static str> {
+        match self {
+            BinaryOperator::Add => Some("Add"),
+            BinaryOperator::Sub => Some("Sub"),
+            BinaryOperator::Mul => Some("Mul"),
+            BinaryOperator::Div => Some("Div"),
+            BinaryOperator::Mod => Some("Rem"),
+            _ => None,
+        }
+    }
+
+    /// How tightly this binds in jasmine, following rust. Higher binds tighter.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::Mul | BinaryOperator::Div | BinaryOperator::Mod => 10,
+            BinaryOperator::Add | BinaryOperator::Sub => 9,
+            BinaryOperator::Shl | BinaryOperator::Shr => 8,
+            BinaryOperator::BitAnd => 7,
+            BinaryOperator::BitXor => 6,
+            BinaryOperator::BitOr => 5,
+            BinaryOperator::Eq
+            | BinaryOperator::Neq
+            | BinaryOperator::Lt
+            | BinaryOperator::Gt
+            | BinaryOperator::Lte
+            | BinaryOperator::Gte => 4,
+            BinaryOperator::And => 3,
+            BinaryOperator::Or => 2,
+        }
+    }
+
+    /// How tightly this binds in java, which differs from rust for bitwise operators
+    pub fn java_precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::Mul | BinaryOperator::Div | BinaryOperator::Mod => 12,
+            BinaryOperator::Add | BinaryOperator::Sub => 11,
+            BinaryOperator::Shl | BinaryOperator::Shr => 10,
+            BinaryOperator::Lt | BinaryOperator::Gt | BinaryOperator::Lte | BinaryOperator::Gte => {
+                9
+            }
+            BinaryOperator::Eq | BinaryOperator::Neq => 8,
+            BinaryOperator::BitAnd => 7,
+            BinaryOperator::BitXor => 6,
+            BinaryOperator::BitOr => 5,
+            BinaryOperator::And => 4,
+            BinaryOperator::Or => 3,
+        }
+    }
+}
+
 impl Rewrite for BinaryOperator {
     fn rewrite(&self) -> String {
         match self {
@@ -80,6 +146,11 @@ impl Rewrite for BinaryOperator {
             BinaryOperator::Gt => ">",
             BinaryOperator::Lte => "<=",
             BinaryOperator::Gte => ">=",
+            BinaryOperator::BitAnd => "&",
+            BinaryOperator::BitOr => "|",
+            BinaryOperator::BitXor => "^",
+            BinaryOperator::Shl => "<<",
+            BinaryOperator::Shr => ">>",
         }
         .to_string()
     }
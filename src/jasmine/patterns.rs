@@ -0,0 +1,201 @@
+use super::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+    /// `_` binds nothing
+    Ident {
+        ident: String,
+        mutable: bool,
+    },
+    Tuple(Vec<Pattern>),
+    Struct {
+        ident: String,
+        fields: Vec<(String, Pattern)>,
+    },
+}
+
+impl Parse for Pattern {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        match pair.as_rule() {
+            Rule::pattern => Self::parse(pair.into_inner().next()?),
+            Rule::binding_pattern => {
+                let mut ident = None;
+                let mut mutable = false;
+
+                for rule in pair.into_inner() {
+                    match rule.as_rule() {
+                        Rule::mut_kwd => mutable = true,
+                        Rule::ident => ident = Some(rule.as_str().to_string()),
+                        _ => {}
+                    }
+                }
+
+                Some(Pattern::Ident {
+                    ident: ident?,
+                    mutable,
+                })
+            }
+            Rule::tuple_pattern => Some(Pattern::Tuple(
+                pair.into_inner()
+                    .filter(|n| n.as_rule() == Rule::pattern)
+                    .map(Pattern::parse)
+                    .collect::<Option<Vec<_>>>()?,
+            )),
+            Rule::struct_pattern => {
+                let mut ident = None;
+                let mut fields = vec![];
+
+                for rule in pair.into_inner() {
+                    match rule.as_rule() {
+                        Rule::ident => ident = Some(rule.as_str().to_string()),
+                        Rule::struct_pattern_field => {
+                            let mut inner = rule.into_inner();
+                            let first = inner.next()?;
+
+                            // `Point { mut x }` is short for `Point { x: mut x }`
+                            let (field, pattern) =
+                                match inner.find(|n| n.as_rule() == Rule::pattern) {
+                                    Some(pattern) => {
+                                        (first.as_str().to_string(), Pattern::parse(pattern)?)
+                                    }
+                                    None => {
+                                        let pattern = Pattern::parse(first)?;
+                                        let Pattern::Ident { ident, .. } = &pattern else {
+                                            return None;
+                                        };
+
+                                        (ident.clone(), pattern)
+                                    }
+                                };
+
+                            fields.push((field, pattern));
+                        }
+                        _ => {}
+                    }
+                }
+
+                Some(Pattern::Struct {
+                    ident: ident?,
+                    fields,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Pattern {
+    /// Java statements binding every name in this pattern to the matching part of `source`
+    pub fn rewrite_bindings(&self, source: &str, ty: Option<Type>) -> Vec<String> {
+        match self {
+            Pattern::Ident { ident, .. } if ident == "_" => vec![],
+            Pattern::Ident { ident, mutable } => {
+                if let Some(ty) = &ty {
+                    rewrite::scope::declare(ident, ty.clone());
+                }
+
+                vec![format!(
+                    "{} {} {} = {};",
+                    if !*mutable { "final" } else { "" },
+                    ty.map(|ty| ty.rewrite()).unwrap_or("var".to_string()),
+                    rewrite_ident(ident),
+                    source
+                )]
+            }
+            Pattern::Tuple(items) => {
+                let item_types = match ty.map(|ty| ty.which) {
+                    Some(WhichType::Tuple(types)) => types,
+                    _ => vec![],
+                };
+
+                items
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(idx, item)| {
+                        item.rewrite_bindings(
+                            &format!("{}._{}", source, idx),
+                            item_types.get(idx).cloned(),
+                        )
+                    })
+                    .collect()
+            }
+            Pattern::Struct { ident, fields } => {
+                let ident = rewrite::scope::resolve_self(ident);
+                let ty = ty.unwrap_or(Type::new(WhichType::Ident(ident.clone())));
+
+                if ty.generic_parts().is_none_or(|(outer, _)| outer != ident) {
+                    rewrite::report(format!(
+                        "Expected `{}` but found the pattern `{} {{ .. }}`",
+                        ty.describe(),
+                        ident
+                    ));
+                }
+
+                fields
+                    .iter()
+                    .flat_map(|(field, pattern)| {
+                        if let Some((outer, _)) = ty.generic_parts() {
+                            rewrite::scope::check_field(&outer, field);
+                        }
+
+                        pattern.rewrite_bindings(
+                            &format!("{}.{}", source, rewrite_ident(field)),
+                            ty.field(field),
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// `let (a, mut b) = expr;` or `let Point { x, y } = expr;`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Destructure {
+    pub pattern: Pattern,
+    pub ty: Option<Type>,
+    pub expr: Expression,
+}
+
+impl Parse for Destructure {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut pattern = None;
+        let mut ty = None;
+        let mut expr = None;
+
+        for rule in pair.into_inner() {
+            match rule.as_rule() {
+                Rule::tuple_pattern | Rule::struct_pattern => pattern = Some(Pattern::parse(rule)?),
+                Rule::ty => ty = Some(Type::parse(rule)?),
+                Rule::expr => expr = Some(Expression::parse(rule)?),
+                _ => {}
+            }
+        }
+
+        Some(Destructure {
+            pattern: pattern?,
+            ty,
+            expr: expr?,
+        })
+    }
+}
+
+impl Destructure {
+    pub fn rewrite(&self) -> String {
+        let ty = self.ty.clone().or_else(|| self.expr.infer_type());
+        let temp = rewrite::temp_ident();
+
+        let mut rewritten = vec![format!(
+            "final {} {} = {};",
+            ty.as_ref()
+                .map(|ty| ty.rewrite())
+                .unwrap_or("var".to_string()),
+            temp,
+            self.expr.rewrite()
+        )];
+
+        rewritten.extend(self.pattern.rewrite_bindings(&temp, ty));
+
+        rewritten.join("\n")
+    }
+}
@@ -8,11 +8,18 @@ pub enum AssignType {
     MulAssign,
     DivAssign,
     ModAssign,
+    BitAndAssign,
+    BitOrAssign,
+    BitXorAssign,
+    ShlAssign,
+    ShrAssign,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Statement {
     pub ident: String,
+    /// The fields and indices assigned into, e.g. `.count` in `self.count += 1`
+    pub after_dot: Option<Box<AfterDotExprType>>,
     pub assign_type: AssignType,
     pub expr: Expression,
 }
@@ -20,18 +27,38 @@ pub struct Statement {
 impl Parse for Statement {
     fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
         let mut ident = None;
+        let mut after_dot: Option<Box<AfterDotExprType>> = None;
         let mut assign_type = None;
         let mut expr = None;
 
         for rule in pair.into_inner() {
             match rule.as_rule() {
-                Rule::ident => ident = Some(rewrite_ident(rule.as_str().to_string())),
+                Rule::place => {
+                    for rule in rule.into_inner() {
+                        match rule.as_rule() {
+                            Rule::ident => ident = Some(rule.as_str().to_string()),
+                            _ => {
+                                let next = AfterDotExprType::parse(rule)?;
+
+                                match &mut after_dot {
+                                    Some(after_dot) => after_dot.push(next),
+                                    None => after_dot = Some(Box::new(next)),
+                                }
+                            }
+                        }
+                    }
+                }
                 Rule::assign => assign_type = Some(AssignType::Assign),
                 Rule::add_assign => assign_type = Some(AssignType::AddAssign),
                 Rule::sub_assign => assign_type = Some(AssignType::SubAssign),
                 Rule::mul_assign => assign_type = Some(AssignType::MulAssign),
                 Rule::div_assign => assign_type = Some(AssignType::DivAssign),
                 Rule::mod_assign => assign_type = Some(AssignType::ModAssign),
+                Rule::bit_and_assign => assign_type = Some(AssignType::BitAndAssign),
+                Rule::bit_or_assign => assign_type = Some(AssignType::BitOrAssign),
+                Rule::bit_xor_assign => assign_type = Some(AssignType::BitXorAssign),
+                Rule::shl_assign => assign_type = Some(AssignType::ShlAssign),
+                Rule::shr_assign => assign_type = Some(AssignType::ShrAssign),
                 Rule::expr => expr = Expression::parse(rule),
                 _ => {}
             }
@@ -39,26 +66,179 @@ impl Parse for Statement {
 
         Some(Self {
             ident: ident?,
+            after_dot,
             assign_type: assign_type?,
             expr: expr?,
         })
     }
 }
 
+impl AssignType {
+    /// The operator of a compound assignment, e.g. `+` for `+=`
+    pub fn binary_op(&self) -> Option<BinaryOperator> {
+        match self {
+            AssignType::Assign => None,
+            AssignType::AddAssign => Some(BinaryOperator::Add),
+            AssignType::SubAssign => Some(BinaryOperator::Sub),
+            AssignType::MulAssign => Some(BinaryOperator::Mul),
+            AssignType::DivAssign => Some(BinaryOperator::Div),
+            AssignType::ModAssign => Some(BinaryOperator::Mod),
+            AssignType::BitAndAssign => Some(BinaryOperator::BitAnd),
+            AssignType::BitOrAssign => Some(BinaryOperator::BitOr),
+            AssignType::BitXorAssign => Some(BinaryOperator::BitXor),
+            AssignType::ShlAssign => Some(BinaryOperator::Shl),
+            AssignType::ShrAssign => Some(BinaryOperator::Shr),
+        }
+    }
+}
+
+impl Rewrite for AssignType {
+    fn rewrite(&self) -> String {
+        match self.binary_op() {
+            Some(op) => format!("{}=", op.rewrite()),
+            None => "=".to_string(),
+        }
+    }
+}
+
 impl Statement {
+    /// The type of the place being assigned to, if known
+    fn target_type(&self) -> Option<Type> {
+        let ty = rewrite::scope::lookup(&self.ident)?;
+
+        match &self.after_dot {
+            Some(after_dot) => after_dot.infer_type(ty),
+            None => Some(ty),
+        }
+    }
+
+    /// The value to assign, converted to the type of the place
+    fn rewrite_value(&self, target: Option<&Type>) -> String {
+        if let (Some(method), Some(ty)) = (self.overload_method(target), target) {
+            return self.expr.rewrite_as(ty.method_params(method).first());
+        }
+
+        match self.assign_type {
+            // the shift distance keeps its own type
+            AssignType::ShlAssign | AssignType::ShrAssign => self.expr.rewrite(),
+            _ => self.expr.rewrite_as(target),
+        }
+    }
+
     pub fn rewrite(&self) -> String {
-        format!(
-            "{} {} {}",
-            self.ident,
-            match self.assign_type {
-                AssignType::Assign => "=",
-                AssignType::AddAssign => "+=",
-                AssignType::SubAssign => "-=",
-                AssignType::MulAssign => "*=",
-                AssignType::DivAssign => "/=",
-                AssignType::ModAssign => "%=",
-            },
-            self.expr.rewrite()
-        )
+        let ident = rewrite_ident(&self.ident);
+        let target = self.target_type();
+
+        if let (Some(after_dot), Some(ty)) = (&self.after_dot, rewrite::scope::lookup(&self.ident))
+        {
+            after_dot.check_chain(ty);
+        }
+
+        let is_compound = self.assign_type != AssignType::Assign;
+
+        let (object, last) = match &self.after_dot {
+            Some(after_dot) => {
+                let (rest, last) = after_dot.split_last();
+
+                match rest {
+                    // the place is read and written, so calls on the way to it must only run once
+                    Some(rest) if is_compound && !rest.is_path() => {
+                        let target = rewrite::temp_ident();
+                        rewrite::hoist(format!(
+                            "final var {} = {}.{};",
+                            target,
+                            ident,
+                            rest.rewrite()
+                        ));
+
+                        (target, Some(last))
+                    }
+                    Some(rest) => (format!("{}.{}", ident, rest.rewrite()), Some(last)),
+                    None => (ident, Some(last)),
+                }
+            }
+            None => (ident, None),
+        };
+
+        if let Some(AfterDotExprType::ArrayIdx { data, .. }) = &last {
+            let mut index = data.rewrite();
+
+            if is_compound && !data.is_repeatable() {
+                let target = rewrite::temp_ident();
+                rewrite::hoist(format!("final var {} = {};", target, index));
+                index = target;
+            }
+
+            let value = self.rewrite_value(target.as_ref());
+
+            // `Vec` elements are replaced with `set`, reading the old value for compound assignments
+            let read = format!("{}.get((Integer) {}).unwrap()", object, index);
+
+            return format!(
+                "{}.set({}, {})",
+                object,
+                index,
+                self.rewrite_compound(read, value, target.as_ref())
+            );
+        }
+
+        let value = self.rewrite_value(target.as_ref());
+
+        let place = match &last {
+            // fields are plain java fields
+            Some(last) => format!("{}.{}", object, last.rewrite()),
+            None => object,
+        };
+
+        match target.as_ref().map(|ty| &ty.which) {
+            // java can not store the `int` result of `byte` arithmetic back into a `Byte`
+            Some(WhichType::I8 | WhichType::I16) if self.assign_type != AssignType::Assign => {
+                format!(
+                    "{} = {}",
+                    place,
+                    self.rewrite_compound(place.clone(), value, target.as_ref())
+                )
+            }
+            // objects have no compound operators, `a += b` through `impl Add` is `a = a.add(b)`
+            _ if self.overload_method(target.as_ref()).is_some() => format!(
+                "{} = {}",
+                place,
+                self.rewrite_compound(place.clone(), value, target.as_ref())
+            ),
+            _ => format!("{} {} {}", place, self.assign_type.rewrite(), value),
+        }
+    }
+
+    /// The method a compound assignment calls on the place through `impl std_trait`
+    fn overload_method(&self, target: Option<&Type>) -> Option<&'static str> {
+        let std_trait = self.assign_type.binary_op()?.overload()?;
+
+        target?.operator_method(std_trait)
+    }
+
+    /// `read op value` for a compound assignment, or just `value` for `=`
+    fn rewrite_compound(&self, read: String, value: String, target: Option<&Type>) -> String {
+        let Some(op) = self.assign_type.binary_op() else {
+            return value;
+        };
+
+        if let Some(method) = self.overload_method(target) {
+            return format!("{}.{}({})", read, method, value);
+        }
+
+        let value = match &self.expr {
+            Expression::Full(_) => format!("({})", value),
+            _ => value,
+        };
+
+        let computed = format!("{} {} {}", read, op.rewrite(), value);
+
+        match target.and_then(|ty| match ty.which {
+            WhichType::I8 | WhichType::I16 => ty.rewrite_primitive(),
+            _ => None,
+        }) {
+            Some(primitive) => format!("(({}) ({}))", primitive, computed),
+            None => computed,
+        }
     }
 }
@@ -0,0 +1,197 @@
+use super::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraitFunction {
+    pub ident: String,
+    pub args: Vec<Arg>,
+    /// `Some` for methods with a default implementation
+    pub body: Option<Vec<BlockPart>>,
+    pub returns: Option<Type>,
+    pub is_static: bool,
+    pub generics: Option<GenericArguments>,
+    pub where_clause: Option<Vec<WhereUnit>>,
+}
+
+impl Parse for TraitFunction {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut ident = None;
+        let mut args = vec![];
+        let mut body = None;
+        let mut returns = None;
+        let mut is_static = true;
+        let mut generics = None;
+        let mut where_clause = None;
+
+        for rule in pair.into_inner() {
+            match rule.as_rule() {
+                Rule::ident => ident = Some(rule.as_str().to_string()),
+                Rule::impl_define_arguments => {
+                    for arg_rule in rule.into_inner() {
+                        match arg_rule.as_rule() {
+                            Rule::define_arguments => {
+                                args = Arg::parse_many(arg_rule)?;
+                            }
+                            Rule::self_kwd => {
+                                is_static = false;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Rule::block => {
+                    body = Some(BlockPart::parse_many(rule)?);
+                }
+                Rule::ty => {
+                    returns = Some(Type::parse(rule)?);
+                }
+                Rule::generic_args => {
+                    generics = Some(GenericArguments::parse(rule)?);
+                }
+                Rule::where_clause => where_clause = Some(WhereUnit::parse_many(rule)?),
+                _ => {}
+            }
+        }
+
+        Some(TraitFunction {
+            ident: ident?,
+            args,
+            body,
+            returns,
+            is_static,
+            generics,
+            where_clause,
+        })
+    }
+}
+
+impl TraitFunction {
+    pub fn rewrite(&self, owner: &Trait) -> String {
+        let trait_ident = &owner.ident;
+
+        // java interfaces cannot declare a static method without a body
+        if self.is_static && self.body.is_none() {
+            rewrite::report(format!(
+                "`fn {}` in trait `{}` has no `self` argument, so it needs a body",
+                self.ident, trait_ident
+            ));
+        }
+
+        // an implementing class can return itself where the interface returns the interface,
+        // but it can not narrow its arguments
+        if self.args.iter().any(|arg| arg.ty.mentions("Self")) {
+            rewrite::report(format!(
+                "`fn {}` in trait `{}` takes `Self`, which java can not express, take `{}` instead",
+                self.ident, trait_ident, trait_ident
+            ));
+        }
+
+        let returns = self.returns.as_ref().map(|ty| {
+            ty.substitute(
+                &["Self".to_string()],
+                &[rewrite::self_type(trait_ident, owner.generics.as_ref())],
+            )
+        });
+
+        if let Some(body) = &self.body {
+            let function = ImplFunction {
+                ident: self.ident.clone(),
+                visibility: Visibility::Public,
+                args: self.args.clone(),
+                body: body.clone(),
+                returns: returns.clone(),
+                is_static: self.is_static,
+                generics: self.generics.clone(),
+                where_clause: self.where_clause.clone(),
+            };
+
+            return format!(
+                "{}\n",
+                function.rewrite_with_modifier(if self.is_static { "static" } else { "default" })
+            );
+        }
+
+        let generics = self
+            .generics
+            .as_ref()
+            .map(|n| n.rewrite(self.where_clause.as_ref()))
+            .unwrap_or_default();
+
+        format!(
+            "{} {} {}({});\n",
+            generics,
+            returns
+                .as_ref()
+                .map(|n| n.rewrite())
+                .unwrap_or("void".to_string()),
+            rewrite_ident(&self.ident),
+            Arg::rewrite_many(self.args.clone(), ", ")
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trait {
+    pub ident: String,
+    pub visibility: Visibility,
+    pub methods: Vec<TraitFunction>,
+    pub generics: Option<GenericArguments>,
+    pub where_clause: Option<Vec<WhereUnit>>,
+}
+
+impl Parse for Trait {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        let mut ident = None;
+        let mut visibility = Visibility::Private;
+        let mut methods = vec![];
+        let mut generics = None;
+        let mut where_clause = None;
+
+        for rule in pair.into_inner() {
+            match rule.as_rule() {
+                Rule::ident => ident = Some(rule.as_str().to_string()),
+                Rule::visibility => visibility = Visibility::parse(rule)?,
+                Rule::trait_fn_def => methods.push(TraitFunction::parse(rule)?),
+                Rule::generic_args => {
+                    generics = Some(GenericArguments::parse(rule)?);
+                }
+                Rule::where_clause => {
+                    where_clause = Some(WhereUnit::parse_many(rule)?);
+                }
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            ident: ident?,
+            visibility,
+            methods,
+            generics,
+            where_clause,
+        })
+    }
+}
+
+impl Trait {
+    pub fn rewrite(&self) -> String {
+        let generics = self
+            .generics
+            .as_ref()
+            .map(|g| g.rewrite(self.where_clause.as_ref()))
+            .unwrap_or_default();
+
+        let mut rewritten = format!(
+            "{}interface {}{} {{\n",
+            self.visibility.rewrite(),
+            self.ident,
+            generics
+        );
+
+        for method in self.methods.iter() {
+            rewritten.push_str(&method.rewrite(self));
+        }
+
+        rewritten.push_str("}\n");
+
+        rewritten
+    }
+}
@@ -28,12 +28,12 @@ impl Parse for ClosureTypeData {
                     if next_is_arg {
                         args.push(ClosureArgument {
                             generic: next_is_generic,
-                            ty: ty,
+                            ty,
                         })
                     } else {
                         ret = Some(ClosureArgument {
                             generic: next_is_generic,
-                            ty: ty,
+                            ty,
                         });
                     }
 
@@ -58,15 +58,28 @@ impl Parse for ClosureTypeData {
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum WhichType {
+    /// `int` or `i32`
     Int,
+    /// `float` or `f64`
     Float,
+    I8,
+    I16,
+    I64,
+    F32,
     Bool,
     String,
     Char,
     Ident(String),
     Closure(ClosureTypeData),
-    Array { ty: Box<Type>, dimensions: usize },
-    Generic { outer: Box<Type>, inner: Vec<Type> },
+    Array {
+        ty: Box<Type>,
+        dimensions: usize,
+    },
+    Generic {
+        outer: Box<Type>,
+        inner: Vec<Type>,
+    },
+    Tuple(Vec<Type>),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -82,15 +95,27 @@ impl Parse for Type {
             match rule.as_rule() {
                 Rule::int_ty => which = Some(WhichType::Int),
                 Rule::float_ty => which = Some(WhichType::Float),
+                Rule::i8_ty => which = Some(WhichType::I8),
+                Rule::i16_ty => which = Some(WhichType::I16),
+                Rule::i64_ty => which = Some(WhichType::I64),
+                Rule::f32_ty => which = Some(WhichType::F32),
                 Rule::char_ty => which = Some(WhichType::Char),
                 Rule::string_ty => which = Some(WhichType::String),
                 Rule::bool_ty => which = Some(WhichType::Bool),
+                Rule::range_ty => which = Some(WhichType::Ident("Range".to_string())),
                 Rule::closure_ty => {
                     let data = ClosureTypeData::parse(rule)?;
                     which = Some(WhichType::Closure(data))
                 }
                 Rule::ident_ty => {
-                    let ident = rule.as_str().to_string();
+                    // modules are flattened, so `geometry::Point` is just `Point`
+                    let ident = rule
+                        .into_inner()
+                        .filter(|n| n.as_rule() == Rule::ident)
+                        .last()?
+                        .as_str()
+                        .to_string();
+
                     which = Some(WhichType::Ident(ident));
                 }
                 Rule::array_ty => {
@@ -113,12 +138,20 @@ impl Parse for Type {
                     let inner = rule
                         .into_inner()
                         .filter(|r| r.as_rule() == Rule::ty)
-                        .map(Type::parse)
-                        .filter_map(|n| n)
+                        .filter_map(Type::parse)
                         .collect_vec();
 
                     which = Some(WhichType::Generic { outer, inner })
                 }
+                Rule::tuple_ty => {
+                    let items = rule
+                        .into_inner()
+                        .filter(|r| r.as_rule() == Rule::ty)
+                        .map(Type::parse)
+                        .collect::<Option<Vec<_>>>()?;
+
+                    which = Some(WhichType::Tuple(items))
+                }
                 _ => {}
             }
         }
@@ -128,6 +161,511 @@ impl Parse for Type {
 }
 
 impl Type {
+    pub fn new(which: WhichType) -> Self {
+        Self { which }
+    }
+
+    /// Replace the generic parameters `params` with the concrete types in `args`
+    pub fn substitute(&self, params: &[String], args: &[Type]) -> Type {
+        match &self.which {
+            WhichType::Ident(ident) => params
+                .iter()
+                .position(|p| p == ident)
+                .and_then(|idx| args.get(idx))
+                .cloned()
+                .unwrap_or(self.clone()),
+            WhichType::Array { ty, dimensions } => Type::new(WhichType::Array {
+                ty: Box::new(ty.substitute(params, args)),
+                dimensions: *dimensions,
+            }),
+            WhichType::Generic { outer, inner } => Type::new(WhichType::Generic {
+                outer: Box::new(outer.substitute(params, args)),
+                inner: inner.iter().map(|n| n.substitute(params, args)).collect(),
+            }),
+            WhichType::Tuple(items) => Type::new(WhichType::Tuple(
+                items.iter().map(|n| n.substitute(params, args)).collect(),
+            )),
+            _ => self.clone(),
+        }
+    }
+
+    /// Whether the ident `ident` appears anywhere in this type
+    pub fn mentions(&self, ident: &str) -> bool {
+        match &self.which {
+            WhichType::Ident(ty) => ty == ident,
+            WhichType::Array { ty, .. } => ty.mentions(ident),
+            WhichType::Generic { outer, inner } => {
+                outer.mentions(ident) || inner.iter().any(|n| n.mentions(ident))
+            }
+            WhichType::Tuple(items) => items.iter().any(|n| n.mentions(ident)),
+            WhichType::Closure(data) => {
+                data.args.iter().any(|n| n.ty.mentions(ident))
+                    || data.ret.as_ref().is_some_and(|n| n.ty.mentions(ident))
+            }
+            _ => false,
+        }
+    }
+
+    /// Split `Outer<Inner, ...>` and plain `Outer` into the outer ident and its type arguments
+    pub fn generic_parts(&self) -> Option<(String, Vec<Type>)> {
+        match &self.which {
+            WhichType::Ident(ident) => Some((ident.clone(), vec![])),
+            WhichType::Generic { outer, inner } => match &outer.which {
+                WhichType::Ident(ident) => Some((ident.clone(), inner.clone())),
+                WhichType::Array { .. } => Some(("Vec".to_string(), vec![outer.as_ref().clone()])),
+                _ => None,
+            },
+            WhichType::Array { ty, dimensions } => Some((
+                "Vec".to_string(),
+                vec![if *dimensions > 1 {
+                    Type::new(WhichType::Array {
+                        ty: ty.clone(),
+                        dimensions: dimensions - 1,
+                    })
+                } else {
+                    ty.as_ref().clone()
+                }],
+            )),
+            _ => None,
+        }
+    }
+
+    /// The type of each item when iterating over this type in a `for` loop
+    pub fn iter_item(&self) -> Option<Type> {
+        match self.generic_parts()? {
+            (outer, inner) if outer == "Vec" => inner.first().cloned(),
+            (outer, _) if outer == "Range" => Some(Type::new(WhichType::Int)),
+            _ => None,
+        }
+    }
+
+    /// The type of a field access on this type
+    pub fn field(&self, field: &str) -> Option<Type> {
+        let (outer, inner) = self.generic_parts()?;
+        let structure = rewrite::scope::find_struct(&outer)?;
+        let params = structure.generics.map(|g| g.args).unwrap_or_default();
+
+        structure
+            .fields
+            .iter()
+            .find(|f| f.ident == field)
+            .map(|f| f.ty.substitute(&params, &inner))
+    }
+
+    /// The type of the data carried by `variant` of this enum type
+    pub fn variant_data(&self, variant: &str) -> Option<Type> {
+        let (outer, inner) = self.generic_parts()?;
+
+        match (outer.as_str(), variant) {
+            ("Option", "Some") | ("Result", "Ok") => return inner.first().cloned(),
+            ("Result", "Err") => return inner.get(1).cloned(),
+            _ => {}
+        }
+
+        let enumeration = rewrite::scope::find_enum(&outer)?;
+        let params = enumeration.generics.map(|g| g.args).unwrap_or_default();
+
+        enumeration
+            .variants
+            .iter()
+            .find(|v| v.ident == variant)?
+            .data
+            .as_ref()
+            .map(|ty| ty.substitute(&params, &inner))
+    }
+
+    /// The return type of calling `method` on a value of this type
+    pub fn method_returns(&self, method: &FunctionCall) -> Option<Type> {
+        let option_of = |ty: Type| {
+            Type::new(WhichType::Generic {
+                outer: Box::new(Type::new(WhichType::Ident("Option".to_string()))),
+                inner: vec![ty],
+            })
+        };
+
+        if method.ident == "clone" && self.clones_with_helper() {
+            return Some(self.clone());
+        }
+
+        if let WhichType::String = self.which {
+            return match method.ident.as_str() {
+                "length" | "index_of" | "compare_to" => Some(Type::new(WhichType::Int)),
+                "char_at" => Some(Type::new(WhichType::Char)),
+                "contains" | "equals" | "is_empty" | "starts_with" | "ends_with" => {
+                    Some(Type::new(WhichType::Bool))
+                }
+                "substring" | "to_upper_case" | "to_lower_case" | "trim" | "repeat" => {
+                    Some(self.clone())
+                }
+                _ => None,
+            };
+        }
+
+        let (outer, inner) = self.generic_parts()?;
+
+        match (outer.as_str(), method.ident.as_str()) {
+            ("Vec", "len") | ("Vec", "sum_int") => Some(Type::new(WhichType::Int)),
+            ("Vec", "sum") | ("Vec", "sum_float") | ("Vec", "average") => {
+                Some(Type::new(WhichType::Float))
+            }
+            ("Vec", "is_empty") => Some(Type::new(WhichType::Bool)),
+            ("Vec", "clone") => Some(self.clone()),
+            ("Vec", "get") | ("Vec", "pop") | ("Vec", "find") => {
+                inner.first().cloned().map(option_of)
+            }
+            ("Vec", "find_index") => Some(option_of(Type::new(WhichType::Int))),
+            ("Vec", "enumerate") => Some(Type::new(WhichType::Array {
+                ty: Box::new(Type::new(WhichType::Tuple(vec![
+                    Type::new(WhichType::Int),
+                    inner.first()?.clone(),
+                ]))),
+                dimensions: 1,
+            })),
+            ("Option", "unwrap")
+            | ("Option", "expect")
+            | ("Option", "unwrap_or")
+            | ("Option", "unwrap_or_else")
+            | ("Result", "unwrap")
+            | ("Result", "unwrap_or")
+            | ("Result", "unwrap_or_else") => inner.first().cloned(),
+            ("Option", "is_some")
+            | ("Option", "is_none")
+            | ("Option", "is_some_and")
+            | ("Result", "is_ok")
+            | ("Result", "is_err")
+            | ("Result", "is_ok_and")
+            | ("Result", "is_err_and") => Some(Type::new(WhichType::Bool)),
+            ("Result", "ok") => inner.first().cloned().map(option_of),
+            ("Result", "err") => inner.get(1).cloned().map(option_of),
+            _ => {
+                let params = rewrite::scope::type_params(&outer);
+                let returns = rewrite::scope::find_method_returns(&outer, &method.ident)??
+                    .substitute(&params, &inner);
+
+                // the type arguments of a generic method or a raw receiver depend on the call
+                // site, so like a generic function the type is left to java
+                let mut unresolved = rewrite::scope::method_type_params(&outer, &method.ident);
+
+                if inner.is_empty() {
+                    unresolved.extend(params);
+                }
+
+                if unresolved.iter().any(|p| returns.mentions(p)) {
+                    return None;
+                }
+
+                Some(returns)
+            }
+        }
+    }
+
+    /// Where this type sits in java's widening order `byte < short < int < long < float < double`
+    pub fn numeric_rank(&self) -> Option<u8> {
+        match self.which {
+            WhichType::I8 => Some(1),
+            WhichType::I16 => Some(2),
+            WhichType::Int => Some(3),
+            WhichType::I64 => Some(4),
+            WhichType::F32 => Some(5),
+            WhichType::Float => Some(6),
+            _ => None,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self.which,
+            WhichType::I8 | WhichType::I16 | WhichType::Int | WhichType::I64
+        )
+    }
+
+    /// Whether a value of this type can be used as `target` without an `as`
+    pub fn widens_to(&self, target: &Type) -> bool {
+        let rank = match self.which {
+            // a char is unsigned, so it only fits in an `int` or wider
+            WhichType::Char => Some(3),
+            _ => self.numeric_rank(),
+        };
+
+        match (rank, target.numeric_rank()) {
+            (Some(rank), Some(target)) => rank <= target,
+            _ => false,
+        }
+    }
+
+    /// The wider of two numeric types, as java promotes them in arithmetic
+    pub fn wider(&self, other: &Type) -> Option<Type> {
+        let rank = |ty: &Type| match ty.which {
+            WhichType::Char => Some(3),
+            _ => ty.numeric_rank(),
+        };
+
+        let wider = if rank(self)? >= rank(other)? {
+            self
+        } else {
+            other
+        };
+
+        match wider.which {
+            WhichType::Char => Some(Type::new(WhichType::Int)),
+            _ => Some(wider.clone()),
+        }
+    }
+
+    /// The name of this type in jasmine, for error messages
+    pub fn describe(&self) -> String {
+        match &self.which {
+            WhichType::Int => "int".to_string(),
+            WhichType::Float => "float".to_string(),
+            WhichType::I8 => "i8".to_string(),
+            WhichType::I16 => "i16".to_string(),
+            WhichType::I64 => "i64".to_string(),
+            WhichType::F32 => "f32".to_string(),
+            WhichType::Bool => "bool".to_string(),
+            WhichType::String => "string".to_string(),
+            WhichType::Char => "char".to_string(),
+            _ => self.rewrite(),
+        }
+    }
+
+    /// The method an operator calls on this type through `impl std_trait`, e.g. `add` for `Add`
+    pub fn operator_method(&self, std_trait: &str) -> Option<&'static str> {
+        let (outer, _) = self.generic_parts()?;
+
+        rewrite::scope::find_impls(&outer)
+            .iter()
+            .find(|imp| imp.std_trait() == Some(std_trait))?
+            .std_method()
+    }
+
+    /// Whether `.clone()` on this type goes through `_clone_`, as strings, boxed primitives and
+    /// type parameters have no public `clone` in java
+    pub fn clones_with_helper(&self) -> bool {
+        match &self.which {
+            WhichType::Ident(ident) => {
+                rewrite::scope::find_struct(ident).is_none()
+                    && rewrite::scope::find_enum(ident).is_none()
+                    && rewrite::scope::find_trait(ident).is_none()
+            }
+            _ => self.rewrite_primitive().is_some() || self.which == WhichType::String,
+        }
+    }
+
+    /// Whether `==` on this type compares with `equals`
+    pub fn has_equals(&self) -> bool {
+        if matches!(self.which, WhichType::String | WhichType::Tuple(_)) {
+            return true;
+        }
+
+        let Some((outer, _)) = self.generic_parts() else {
+            return false;
+        };
+
+        matches!(outer.as_str(), "Vec" | "Option" | "Result")
+            || rewrite::scope::derives(&outer, "PartialEq")
+            || rewrite::scope::derives(&outer, "Eq")
+            || rewrite::scope::implements_std(&outer, "PartialEq")
+    }
+
+    /// Whether a field of this type can be part of `#[derive(Clone)]` or `#[derive(PartialEq)]`.
+    /// Type parameters are allowed, the derived method uses their own `clone` or `equals`.
+    pub fn supports_derive(&self, derive: &str) -> bool {
+        let implemented = |ident: &str| match derive {
+            "PartialEq" => {
+                rewrite::scope::derives(ident, "PartialEq")
+                    || rewrite::scope::derives(ident, "Eq")
+                    || rewrite::scope::implements_std(ident, "PartialEq")
+            }
+            _ => rewrite::scope::derives(ident, derive),
+        };
+
+        match &self.which {
+            WhichType::Closure(_) => false,
+            WhichType::Array { ty, .. } => ty.supports_derive(derive),
+            WhichType::Tuple(items) => items.iter().all(|n| n.supports_derive(derive)),
+            WhichType::Ident(ident) if rewrite::scope::find_trait(ident).is_some() => false,
+            WhichType::Ident(ident)
+                if rewrite::scope::find_struct(ident).is_some()
+                    || rewrite::scope::find_enum(ident).is_some() =>
+            {
+                implemented(ident)
+            }
+            WhichType::Generic { .. } => {
+                let Some((outer, inner)) = self.generic_parts() else {
+                    return false;
+                };
+
+                (matches!(outer.as_str(), "Vec" | "Option" | "Result") || implemented(&outer))
+                    && inner.iter().all(|n| n.supports_derive(derive))
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether this is a type parameter with one of `traits` among its bounds
+    pub fn is_bounded_by(&self, traits: &[&str]) -> bool {
+        let WhichType::Ident(ident) = &self.which else {
+            return false;
+        };
+
+        rewrite::scope::bounds(ident).iter().any(
+            |bound| matches!(bound.generic_parts(), Some((n, _)) if traits.contains(&n.as_str())),
+        )
+    }
+
+    /// Whether `<` on this type compares with `compareTo`, including type parameters bounded by `Ord`
+    pub fn is_comparable(&self) -> bool {
+        match &self.which {
+            WhichType::String => true,
+            WhichType::Ident(ident) if !rewrite::scope::bounds(ident).is_empty() => {
+                self.is_bounded_by(&["Ord", "PartialOrd", "Comparable"])
+            }
+            _ => {
+                self.operator_method("PartialOrd").is_some()
+                    || self.operator_method("Ord").is_some()
+            }
+        }
+    }
+
+    /// The declared parameter types of `method` on this type, where they are known
+    pub fn method_params(&self, method: &str) -> Vec<Type> {
+        let Some((outer, inner)) = self.generic_parts() else {
+            return vec![];
+        };
+
+        // `None` for a return type that depends on the closure
+        let closure = |args: Vec<&Type>, ret: Option<Type>| {
+            Type::new(WhichType::Closure(ClosureTypeData {
+                args: args
+                    .into_iter()
+                    .map(|ty| ClosureArgument {
+                        generic: false,
+                        ty: ty.clone(),
+                    })
+                    .collect(),
+                ret: Some(Box::new(match ret {
+                    Some(ty) => ClosureArgument { generic: false, ty },
+                    None => ClosureArgument {
+                        generic: true,
+                        ty: Type::new(WhichType::Ident("U".to_string())),
+                    },
+                })),
+            }))
+        };
+
+        let bool = Type::new(WhichType::Bool);
+
+        match (outer.as_str(), method, inner.first()) {
+            ("Vec", "push", Some(item))
+            | ("Option", "unwrap_or", Some(item))
+            | ("Result", "unwrap_or", Some(item)) => vec![item.clone()],
+            ("Vec", "retain" | "find" | "find_index", Some(item))
+            | ("Option", "is_some_and", Some(item))
+            | ("Result", "is_ok_and", Some(item)) => vec![closure(vec![item], Some(bool))],
+            ("Vec", "sort_by", Some(item)) => {
+                vec![closure(vec![item], Some(Type::new(WhichType::Int)))]
+            }
+            ("Vec", "set" | "insert", Some(item)) => vec![Type::new(WhichType::Int), item.clone()],
+            ("Option" | "Result", "map", Some(item)) => vec![closure(vec![item], None)],
+            ("Option" | "Result", "unwrap_or_else", Some(item)) => {
+                vec![closure(vec![], Some(item.clone()))]
+            }
+            ("Option" | "Result", "map_or", Some(item)) => vec![
+                Type::new(WhichType::Ident("U".to_string())),
+                closure(vec![item], None),
+            ],
+            ("Result", "is_err_and", _) => match inner.get(1) {
+                Some(err) => vec![closure(vec![err], Some(bool))],
+                None => vec![],
+            },
+            ("Result", "map_err", _) => match inner.get(1) {
+                Some(err) => vec![closure(vec![err], None)],
+                None => vec![],
+            },
+            _ => {
+                let params = rewrite::scope::find_struct(&outer)
+                    .and_then(|s| s.generics)
+                    .or_else(|| rewrite::scope::find_enum(&outer).and_then(|e| e.generics))
+                    .map(|g| g.args)
+                    .unwrap_or_default();
+
+                rewrite::scope::find_method(&outer, method)
+                    .map(|f| f.param_types())
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|ty| ty.substitute(&params, &inner))
+                    .collect()
+            }
+        }
+    }
+
+    /// The java value of `Default::default()` for this type, `None` if it has none
+    pub fn default_value(&self) -> Option<String> {
+        let value = match &self.which {
+            WhichType::Int => "0".to_string(),
+            WhichType::Float => "0.0".to_string(),
+            WhichType::I8 => "(byte) 0".to_string(),
+            WhichType::I16 => "(short) 0".to_string(),
+            WhichType::I64 => "0L".to_string(),
+            WhichType::F32 => "0.0f".to_string(),
+            WhichType::Bool => "false".to_string(),
+            WhichType::Char => "'\\0'".to_string(),
+            WhichType::String => "\"\"".to_string(),
+            WhichType::Array { .. } => "new Vec<>()".to_string(),
+            WhichType::Tuple(items) => format!(
+                "new {}<>({})",
+                rewrite::add_tuple(items.len()),
+                items
+                    .iter()
+                    .map(|n| n.default_value())
+                    .collect::<Option<Vec<_>>>()?
+                    .join(", ")
+            ),
+            _ => match self.generic_parts()?.0.as_str() {
+                "Vec" => "new Vec<>()".to_string(),
+                "Option" => "Option.None()".to_string(),
+                ident if rewrite::scope::derives(ident, "Default") => {
+                    format!("{}.default_value()", ident)
+                }
+                _ => return None,
+            },
+        };
+
+        Some(value)
+    }
+
+    /// This type as part of a java identifier, e.g. `Tuple2IntegerInteger` for `(int, int)`.
+    /// Each generic type takes a fixed number of arguments, so the parts are not ambiguous.
+    pub fn mangle(&self) -> String {
+        let mangled = self
+            .rewrite()
+            .chars()
+            .filter(|n| n.is_ascii_alphanumeric() || *n == '_')
+            .collect::<String>();
+
+        let mut chars = mangled.chars();
+
+        match chars.next() {
+            Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+            None => mangled,
+        }
+    }
+
+    /// The unboxed java type, if there is one
+    pub fn rewrite_primitive(&self) -> Option<String> {
+        match self.which {
+            WhichType::Int => Some("int".to_string()),
+            WhichType::Float => Some("double".to_string()),
+            WhichType::I8 => Some("byte".to_string()),
+            WhichType::I16 => Some("short".to_string()),
+            WhichType::I64 => Some("long".to_string()),
+            WhichType::F32 => Some("float".to_string()),
+            WhichType::Char => Some("char".to_string()),
+            WhichType::Bool => Some("boolean".to_string()),
+            _ => None,
+        }
+    }
+
     pub fn rewrite(&self) -> String {
         let mut rewritten = "".to_string();
 
@@ -157,6 +695,10 @@ impl Type {
             WhichType::Int => {
                 rewritten.push_str("Integer");
             }
+            WhichType::I8 => rewritten.push_str("Byte"),
+            WhichType::I16 => rewritten.push_str("Short"),
+            WhichType::I64 => rewritten.push_str("Long"),
+            WhichType::F32 => rewritten.push_str("Float"),
             WhichType::Generic { outer, inner } => {
                 let unboxed_outer = outer.as_ref().clone();
                 let unboxed_inner = inner;
@@ -168,10 +710,19 @@ impl Type {
                 ))
             }
             WhichType::String => rewritten.push_str("String"),
+            WhichType::Ident(ty) if ty == "Self" => match rewrite::scope::lookup("self") {
+                Some(self_ty) => rewritten.push_str(&self_ty.rewrite()),
+                None => rewritten.push_str(&ty),
+            },
             WhichType::Ident(ty) => {
                 rewritten.push_str(&ty);
             }
             WhichType::Closure(data) => rewritten.push_str(&rewrite::add_closure(data)),
+            WhichType::Tuple(items) => rewritten.push_str(&format!(
+                "{}<{}>",
+                rewrite::add_tuple(items.len()),
+                items.iter().map(|n| n.rewrite()).join(", ")
+            )),
         }
 
         rewritten
@@ -0,0 +1,33 @@
+use super::*;
+
+/// `pub`, `pub(crate)` or nothing, on items, fields and methods
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Visibility {
+    Public,
+    Crate,
+    #[default]
+    Private,
+}
+
+impl Parse for Visibility {
+    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+        match pair
+            .into_inner()
+            .any(|rule| rule.as_rule() == Rule::crate_kwd)
+        {
+            true => Some(Visibility::Crate),
+            false => Some(Visibility::Public),
+        }
+    }
+}
+
+impl Visibility {
+    /// The java access modifier, package-private has none
+    pub fn rewrite(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public ",
+            Visibility::Crate => "",
+            Visibility::Private => "private ",
+        }
+    }
+}
@@ -12,25 +12,23 @@ mod rewrite;
 
 use crate::prelude::*;
 use clap::Parser;
-use std::{fs::File, io::Write, path::PathBuf, process::Command};
+use std::{fs::File, io::Write, path::PathBuf};
 
 fn main() -> Result<()> {
     let args = args::JasmineCli::parse();
-    let ast = parser::parse(PathBuf::new().join(&args.input.first().unwrap()))?;
-
-    let input = &args
-        .input
-        .first()
-        .unwrap()
-        .split('.')
-        .next()
-        .unwrap()
+    let root = PathBuf::new().join(args.input.first().unwrap());
+    let modules = parser::parse_program(root.clone())?;
+
+    let input = &root
+        .file_stem()
+        .context("Input is not a file")?
+        .to_string_lossy()
         .to_string();
 
     let input_first_upper = input.chars().next().unwrap().to_uppercase().to_string() + &input[1..];
 
+    let r = rewrite::rewrite(modules, &input_first_upper)?;
     let mut f = File::create(PathBuf::new().join(format!("{}.java", &input_first_upper)))?;
-    let r = rewrite::rewrite(ast, &input_first_upper);
 
     writeln!(f, "{}", r)?;
 
@@ -1,4 +1,4 @@
-use std::{fs::File, io::Read, path::PathBuf};
+use std::{collections::HashMap, fs::File, io::Read, path::PathBuf};
 
 use crate::jasmine::*;
 use pest::Parser;
@@ -8,7 +8,8 @@ use pest_derive::Parser;
 #[grammar = "jasmine.pest"]
 struct JasmineParser;
 
-pub fn parse(file: PathBuf) -> Result<Vec<JasmineProgramComponent>> {
+/// Parse `file` as the module `path`
+pub fn parse(file: PathBuf, path: Vec<String>) -> Result<ProgramModule> {
     let mut file_str = String::new();
 
     File::open(file)?.read_to_string(&mut file_str)?;
@@ -18,10 +19,14 @@ pub fn parse(file: PathBuf) -> Result<Vec<JasmineProgramComponent>> {
 
     match pest_parsed {
         Result::Ok(Result::Ok(pest_parsed)) => {
-            let components =
-                JasmineProgramComponent::parse_many(pest_parsed).context("Could not parse")?;
+            let components = JasmineProgramComponent::parse_many(pest_parsed.clone())
+                .context("Could not parse")?;
 
-            Ok(components)
+            Ok(ProgramModule {
+                path,
+                components,
+                paths: ProgramModule::collect_paths(pest_parsed),
+            })
         }
         Err(e) => {
             println!("{:?}", e);
@@ -30,3 +35,154 @@ pub fn parse(file: PathBuf) -> Result<Vec<JasmineProgramComponent>> {
         _ => unreachable!(),
     }
 }
+
+/// Parse `file` and every file it pulls in with `mod`
+fn load_module(file: PathBuf, path: Vec<String>, loaded: &mut Vec<ProgramModule>) -> Result<()> {
+    let module = parse(file.clone(), path.clone())
+        .with_context(|| format!("Failed to parse {}", file.display()))?;
+
+    // `mod b;` in `a.jasmine` lives in `a/b.jasmine`, like rust
+    let parent = file.parent().map(PathBuf::from).unwrap_or_default();
+    let dir = if path.is_empty() || file.file_stem().is_some_and(|n| n == "mod") {
+        parent
+    } else {
+        parent.join(path.last().unwrap())
+    };
+
+    let submodules = module
+        .components
+        .iter()
+        .filter_map(|n| {
+            let JasmineProgramComponent::Mod(module) = n else {
+                return None;
+            };
+            Some(module.ident.clone())
+        })
+        .collect_vec();
+
+    loaded.push(module);
+
+    for ident in submodules {
+        let flat = dir.join(format!("{}.jasmine", ident));
+        let nested = dir.join(&ident).join("mod.jasmine");
+
+        let mut module_path = path.clone();
+        module_path.push(ident.clone());
+
+        let module_file = match (flat.exists(), nested.exists()) {
+            (true, false) => flat,
+            (false, true) => nested,
+            (true, true) => bail!(
+                "Module `{}` found at both {} and {}",
+                display_path(&module_path),
+                flat.display(),
+                nested.display()
+            ),
+            (false, false) => bail!(
+                "File not found for module `{}`, expected {} or {}",
+                display_path(&module_path),
+                flat.display(),
+                nested.display()
+            ),
+        };
+
+        load_module(module_file, module_path, loaded)?;
+    }
+
+    Ok(())
+}
+
+/// Parse a program starting at its root file, loading all of its modules and checking that
+/// the names each module uses are defined in it or imported with `use`
+pub fn parse_program(root: PathBuf) -> Result<Vec<ProgramModule>> {
+    let mut loaded = vec![];
+    load_module(root, vec![], &mut loaded)?;
+
+    // everything ends up in one java class, so names must be unique across modules
+    let mut defined: HashMap<&String, &Vec<String>> = HashMap::new();
+
+    for module in loaded.iter() {
+        for component in module.components.iter() {
+            if let JasmineProgramComponent::Mod(_) = component {
+                continue;
+            }
+
+            let Some(ident) = component.item_ident() else {
+                continue;
+            };
+
+            if let Some(other) = defined.insert(ident, &module.path) {
+                bail!(
+                    "`{}` is defined in both `{}` and `{}`",
+                    ident,
+                    display_path(other),
+                    display_path(&module.path)
+                );
+            }
+        }
+    }
+
+    for module in loaded.iter() {
+        for component in module.components.iter() {
+            let JasmineProgramComponent::Use(import) = component else {
+                continue;
+            };
+
+            let (module_path, items) = import.split();
+
+            let Some(target) = resolve_module(&loaded, &module.path, &module_path)
+                .and_then(|path| loaded.iter().find(|m| m.path == path))
+            else {
+                bail!(
+                    "Unresolved import `{}` in `{}`: no module `{}`",
+                    import.path.join("::"),
+                    display_path(&module.path),
+                    module_path.join("::")
+                );
+            };
+
+            for item in items {
+                let Some(component) = target
+                    .components
+                    .iter()
+                    .find(|n| n.item_ident() == Some(&item))
+                else {
+                    bail!(
+                        "Unresolved import `{}` in `{}`: no `{}` in `{}`",
+                        import.path.join("::"),
+                        display_path(&module.path),
+                        item,
+                        display_path(&target.path)
+                    );
+                };
+
+                // a module sees the private items of its parents, like rust
+                if component.is_private() && !module.path.starts_with(&target.path) {
+                    bail!(
+                        "`{}` is private to `{}`, mark it `pub` to import it in `{}`",
+                        item,
+                        display_path(&target.path),
+                        display_path(&module.path)
+                    );
+                }
+            }
+        }
+    }
+
+    let errors = loaded
+        .iter()
+        .flat_map(|module| {
+            module
+                .paths
+                .iter()
+                .filter_map(|path| path_error(&loaded, &module.path, path))
+        })
+        .unique()
+        .collect_vec();
+
+    if !errors.is_empty() {
+        bail!(errors.join("\n"));
+    }
+
+    Ok(loaded)
+}
@@ -5,18 +5,122 @@ public class Builtins {
 	// JASMINE_BUILTINS_START
 
 	public static void print(String formatstr, Object... args) {
-		System.out.printf(formatstr.replace("{}", "%s"), args);
+		System.out.print(format(formatstr, args));
 	}
 
 	public static void println(String formatstr, Object... args) {
 		print(formatstr + "\n", args);
 	}
 
+	// `{}` is replaced with `toString`, `{:?}` with `_debug_`, `{{` and `}}` are escaped braces
 	public static String format(String formatstr, Object... args) {
-		return String.format(formatstr.replace("{}", "%s"), args);
+		StringBuilder builder = new StringBuilder();
+		int arg = 0;
+
+		for (int i = 0; i < formatstr.length(); i++) {
+			boolean placeholder = formatstr.startsWith("{}", i) || formatstr.startsWith("{:?}", i);
+
+			if (placeholder && arg >= args.length) {
+				throw new RuntimeException("missing argument for the placeholder at " + i + " in \"" + formatstr + "\"");
+			}
+
+			if (formatstr.startsWith("{{", i) || formatstr.startsWith("}}", i)) {
+				builder.append(formatstr.charAt(i));
+				i += 1;
+			} else if (formatstr.startsWith("{}", i)) {
+				builder.append(args[arg++]);
+				i += 1;
+			} else if (formatstr.startsWith("{:?}", i)) {
+				builder.append(_debug_(args[arg++]));
+				i += 3;
+			} else {
+				builder.append(formatstr.charAt(i));
+			}
+		}
+
+		return builder.toString();
+	}
+
+	public static int _asInt_(Object value) {
+		if (value instanceof Number) {
+			return ((Number) value).intValue();
+		} else if (value instanceof Character) {
+			return (int) (char) (Character) value;
+		} else if (value instanceof Boolean) {
+			return ((Boolean) value) ? 1 : 0;
+		} else {
+			throw new RuntimeException("cannot cast " + value + " to int");
+		}
+	}
+
+	public static long _asLong_(Object value) {
+		if (value instanceof Number) {
+			return ((Number) value).longValue();
+		} else if (value instanceof Character) {
+			return (long) (char) (Character) value;
+		} else if (value instanceof Boolean) {
+			return ((Boolean) value) ? 1 : 0;
+		} else {
+			throw new RuntimeException("cannot cast " + value + " to long");
+		}
+	}
+
+	public static double _asFloat_(Object value) {
+		if (value instanceof Number) {
+			return ((Number) value).doubleValue();
+		} else if (value instanceof Character) {
+			return (double) (char) (Character) value;
+		} else {
+			throw new RuntimeException("cannot cast " + value + " to float");
+		}
+	}
+
+	public static char _asChar_(Object value) {
+		if (value instanceof Character) {
+			return (Character) value;
+		} else if (value instanceof Number) {
+			return (char) ((Number) value).intValue();
+		} else {
+			throw new RuntimeException("cannot cast " + value + " to char");
+		}
+	}
+
+	public static <T> T _matchFailed_() {
+		throw new RuntimeException("Not all match arms were covered in this statement");
+	}
+
+	// implemented by the classes of `#[derive(Clone)]`
+	public interface _Clone_ {
+		Object clone();
+	}
+
+	@SuppressWarnings("unchecked")
+	public static <T> T _clone_(T value) {
+		if (value instanceof _Clone_) {
+			return (T) ((_Clone_) value).clone();
+		} else {
+			return value;
+		}
+	}
+
+	// implemented by the classes of `#[derive(Debug)]` and `impl Debug`
+	public interface _Debug_ {
+		String fmtDebug();
 	}
 
-	public static class Option<T> {
+	public static String _debug_(Object value) {
+		if (value instanceof _Debug_) {
+			return ((_Debug_) value).fmtDebug();
+		} else if (value instanceof String) {
+			return "\"" + value + "\"";
+		} else if (value instanceof Character) {
+			return "'" + value + "'";
+		} else {
+			return String.valueOf(value);
+		}
+	}
+
+	public static class Option<T> implements _Clone_ {
 		public static final int _Some = 1;
 		public static final int _None = 2;
 		int currentVariant;
@@ -145,10 +249,35 @@ public class Builtins {
 				throw new RuntimeException("Not all match arms were covered in this statement");
 			}
 		}
+
+		public Option<T> clone() {
+			return new Option<T>(this.currentVariant, _clone_(this.SomeData));
+		}
+
+		public boolean equals(Object other) {
+			if (!(other instanceof Option)) {
+				return false;
+			}
+
+			Option<?> o = (Option<?>) other;
+			return this.currentVariant == o.currentVariant && Objects.equals(this.SomeData, o.SomeData);
+		}
+
+		public int hashCode() {
+			return Objects.hash(this.currentVariant, this.SomeData);
+		}
+
+		public String toString() {
+			if (this.is(Option._Some)) {
+				return "Some(" + _debug_(this.SomeData) + ")";
+			} else {
+				return "None";
+			}
+		}
 	}
 
 
-	public static class Result<T, E> {
+	public static class Result<T, E> implements _Clone_ {
 		public static final int _Ok = 1;
 		public static final int _Err = 2;
 		int currentVariant;
@@ -309,9 +438,36 @@ public class Builtins {
 				throw new RuntimeException("Not all match arms were covered in this statement");
 			}
 		}
+
+		public Result<T, E> clone() {
+			return new Result<T, E>(this.currentVariant, _clone_(this.OkData), _clone_(this.ErrData));
+		}
+
+		public boolean equals(Object other) {
+			if (!(other instanceof Result)) {
+				return false;
+			}
+
+			Result<?, ?> o = (Result<?, ?>) other;
+			return this.currentVariant == o.currentVariant
+					&& Objects.equals(this.OkData, o.OkData)
+					&& Objects.equals(this.ErrData, o.ErrData);
+		}
+
+		public int hashCode() {
+			return Objects.hash(this.currentVariant, this.OkData, this.ErrData);
+		}
+
+		public String toString() {
+			if (this.is(Result._Ok)) {
+				return "Ok(" + _debug_(this.OkData) + ")";
+			} else {
+				return "Err(" + _debug_(this.ErrData) + ")";
+			}
+		}
 	}
 
-	public static class Vec<T> implements Iterable<T> {
+	public static class Vec<T> implements Iterable<T>, _Clone_ {
 		ArrayList<T> inner;
 
 		public Iterator<T> iterator() {
@@ -322,6 +478,10 @@ public class Builtins {
 			this.inner = new ArrayList<T>();
 		}
 
+		public static <T> Vec<T> _new() {
+			return new Vec<T>();
+		}
+
 		public static <T> Vec<T> from(T... items) {
 			Vec<T> vec = new Vec<T>();
 
@@ -332,6 +492,17 @@ public class Builtins {
 			return vec;
 		}
 
+		// `[item; count]`, every element after the first is a clone so rows don't alias
+		public static <T> Vec<T> repeat(T item, int count) {
+			Vec<T> vec = new Vec<T>();
+
+			for (int i = 0; i < count; i++) {
+				vec.inner.add(i == 0 ? item : _clone_(item));
+			}
+
+			return vec;
+		}
+
 		public void push(T item) {
 			this.inner.add(item);
 		}
@@ -352,6 +523,10 @@ public class Builtins {
 			}
 		}
 
+		public void set(int index, T item) {
+			this.inner.set(index, item);
+		}
+
 		public void insert(int index, T item) {
 			this.inner.add(index, item);
 		}
@@ -402,16 +577,42 @@ public class Builtins {
 
 		public Vec<T> clone() {
 			Vec<T> vec = new Vec<T>();
-			vec.inner = (ArrayList<T>) this.inner.clone();
+
+			for (T item : this.inner) {
+				vec.inner.add(_clone_(item));
+			}
+
 			return vec;
 		}
 
+		public boolean equals(Object other) {
+			return other instanceof Vec && this.inner.equals(((Vec<?>) other).inner);
+		}
+
+		public int hashCode() {
+			return this.inner.hashCode();
+		}
+
+		public String toString() {
+			StringBuilder builder = new StringBuilder("[");
+
+			for (int i = 0; i < this.inner.size(); i++) {
+				if (i > 0) {
+					builder.append(", ");
+				}
+
+				builder.append(_debug_(this.inner.get(i)));
+			}
+
+			return builder.append("]").toString();
+		}
+
 		public Double sumFloat() {
-			return this.inner.stream().mapToDouble(x -> (Double) x).sum(); // java creators, where clause wen
+			return this.inner.stream().mapToDouble(x -> ((Number) x).doubleValue()).sum(); // java creators, where clause wen
 		}
 
 		public Integer sumInt() {
-			return this.inner.stream().mapToInt(x -> (Integer) x).sum();
+			return this.inner.stream().mapToInt(x -> ((Number) x).intValue()).sum();
 		}
 
 		public Double sum() {
@@ -436,6 +637,17 @@ public class Builtins {
 			return Option.None();
 		}
 
+		// `Tuple2` is generated by the compiler, see `rewrite::add_tuple`
+		public Vec<Tuple2<Integer, T>> enumerate() {
+			Vec<Tuple2<Integer, T>> vec = new Vec<Tuple2<Integer, T>>();
+
+			for (int i = 0; i < this.len(); i++) {
+				vec.push(new Tuple2<Integer, T>(i, this.inner.get(i)));
+			}
+
+			return vec;
+		}
+
 		public Option<Integer> findIndex(Closure_Generic_RetBoolean<T> function) {
 			for (Integer idx : new Range(0, this.len(), false)) {
 				T item = this.get(idx).unwrap();
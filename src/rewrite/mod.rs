@@ -1,62 +1,106 @@
-use std::collections::HashMap;
+pub mod scope;
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeSet, HashMap},
+};
 
 use crate::jasmine::*;
 
-static mut CREATE_CLOSURES: Option<HashMap<String, ClosureTypeData>> = None;
+thread_local! {
+    static CREATE_CLOSURES: RefCell<HashMap<String, ClosureTypeData>> = RefCell::new(HashMap::new());
+    static CREATE_TUPLES: RefCell<BTreeSet<usize>> = const { RefCell::new(BTreeSet::new()) };
+    static ERRORS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+    static TEMP_COUNTER: Cell<usize> = const { Cell::new(0) };
+    static HOISTED: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
 
-const BUILTINS_JAVA: &'static str = include_str!("Builtins.java");
+/// Emit `stmt` before the statement currently being rewritten
+pub fn hoist(stmt: String) {
+    HOISTED.with(|hoisted| hoisted.borrow_mut().push(stmt));
+}
 
-pub fn add_closure(closure: ClosureTypeData) -> String {
-    let args = closure
-        .args
-        .iter()
-        .cloned()
-        .map(|n| {
-            if n.generic {
-                "Generic".to_string()
-            } else {
-                n.ty.rewrite()
+/// Rewrite a statement with `f`, placing anything hoisted out of its expressions before it
+pub fn with_hoisted(f: impl FnOnce() -> String) -> String {
+    let (hoisted, rewritten) = collect_hoisted(f);
+
+    hoisted.iter().map(|n| format!("{}\n", n)).join("") + &rewritten
+}
+
+/// Rewrite with `f`, returning what was hoisted out of it instead of placing it
+pub fn collect_hoisted<T>(f: impl FnOnce() -> T) -> (Vec<String>, T) {
+    let outer = HOISTED.with(|hoisted| hoisted.take());
+    let rewritten = f();
+    let hoisted = HOISTED.with(|hoisted| hoisted.replace(outer));
+
+    (hoisted, rewritten)
+}
+
+/// An if-else chain of `(hoisted, cond, body)` branches, and the body of the final `else`.
+/// What is hoisted out of a condition only runs once the branches before it failed,
+/// so a branch that hoists starts a new chain in the `else` of the one before.
+pub fn rewrite_if_chain(
+    branches: Vec<(Vec<String>, String, String)>,
+    else_body: Option<String>,
+) -> String {
+    let mut rest = else_body.map(|body| format!("{{\n{}\n}}", body));
+
+    for (idx, (hoisted, cond, body)) in branches.into_iter().enumerate().rev() {
+        let mut rewritten = format!("if ({}) {{\n{}\n}}", cond, body);
+
+        if let Some(rest) = rest {
+            rewritten.push_str(&format!(" else {}", rest));
+        }
+
+        if !hoisted.is_empty() {
+            rewritten = format!("{}\n{}", hoisted.join("\n"), rewritten);
+
+            if idx > 0 {
+                rewritten = format!("{{\n{}\n}}", rewritten);
             }
-        })
-        .map(|n| {
-            n.char_indices()
-                .map(|(idx, ch)| {
-                    if idx == 0 {
-                        ch.to_ascii_uppercase()
-                    } else {
-                        ch
-                    }
-                })
-                .collect::<String>()
-        })
-        .join("");
+        }
+
+        rest = Some(rewritten);
+    }
+
+    rest.unwrap_or_default()
+}
+
+/// A fresh identifier for compiler-generated temporaries
+pub fn temp_ident() -> String {
+    let idx = TEMP_COUNTER.with(|counter| counter.replace(counter.get() + 1));
+
+    format!("_temp{}_", idx)
+}
+
+/// Report a compile error. Rewriting continues so that every error is reported at once.
+pub fn report<S>(error: S)
+where
+    S: ToString,
+{
+    ERRORS.with(|errors| errors.borrow_mut().push(error.to_string()));
+}
+
+const BUILTINS_JAVA: &str = include_str!("Builtins.java");
+
+pub fn add_closure(closure: ClosureTypeData) -> String {
+    let mangle = |arg: &ClosureArgument| match arg.generic {
+        true => "Generic".to_string(),
+        false => arg.ty.mangle(),
+    };
+
+    let args = closure.args.iter().map(mangle).join("");
 
     let ret = closure
         .ret
-        .clone()
-        .map(|n| n.as_ref().clone())
-        .map(|n| {
-            if n.generic {
-                "Generic".to_string()
-            } else {
-                n.ty.rewrite()
-                    .char_indices()
-                    .map(|(idx, ch)| {
-                        if idx == 0 {
-                            ch.to_ascii_uppercase()
-                        } else {
-                            ch
-                        }
-                    })
-                    .collect()
-            }
-        })
+        .as_deref()
+        .map(mangle)
         .unwrap_or("void".to_string());
 
     let mut rewritten = format!(
         "Closure_{}_Ret{}",
         {
-            if args.len() == 0 {
+            if args.is_empty() {
                 "void".to_string()
             } else {
                 args
@@ -65,17 +109,11 @@ pub fn add_closure(closure: ClosureTypeData) -> String {
         ret
     );
 
-    unsafe {
-        if let Some(closures) = &mut CREATE_CLOSURES {
-            closures.insert(rewritten.clone(), closure.clone());
-        } else {
-            CREATE_CLOSURES = Some(HashMap::new());
-            CREATE_CLOSURES
-                .as_mut()
-                .unwrap()
-                .insert(rewritten.clone(), closure.clone());
-        }
-    }
+    CREATE_CLOSURES.with(|closures| {
+        closures
+            .borrow_mut()
+            .insert(rewritten.clone(), closure.clone())
+    });
 
     let generics = closure
         .args
@@ -106,20 +144,338 @@ pub fn add_closure(closure: ClosureTypeData) -> String {
     rewritten
 }
 
+/// Request a `Tuple{arity}` class, returning its name
+pub fn add_tuple(arity: usize) -> String {
+    CREATE_TUPLES.with(|tuples| tuples.borrow_mut().insert(arity));
+
+    format!("Tuple{}", arity)
+}
+
+/// The type of `self` inside the methods of `ident`
+pub fn self_type(ident: &str, generics: Option<&GenericArguments>) -> Type {
+    let ty = Type::new(WhichType::Ident(ident.to_string()));
+
+    match generics {
+        Some(generics) => Type::new(WhichType::Generic {
+            outer: Box::new(ty),
+            inner: generics
+                .args
+                .iter()
+                .map(|arg| Type::new(WhichType::Ident(arg.clone())))
+                .collect(),
+        }),
+        None => ty,
+    }
+}
+
+/// Whether a class renders `{:?}` itself, from `#[derive(Debug)]` or `impl Debug`
+fn has_debug(impls: &[Impl], derives: &[String]) -> bool {
+    derives.iter().any(|n| n == "Debug")
+        || impls.iter().any(|imp| imp.format_trait() == Some("Debug"))
+}
+
+/// Whether a class has `compareTo`, from `impl PartialOrd` or `impl Ord`
+fn has_compare(impls: &[Impl]) -> bool {
+    impls
+        .iter()
+        .any(|imp| matches!(imp.std_trait(), Some("PartialOrd" | "Ord")))
+}
+
+/// The interfaces of the class `ty`, from its trait impls and its derives
+fn rewrite_implements(ty: &Type, impls: &[Impl], derives: &[String]) -> String {
+    let traits = impls
+        .iter()
+        .filter(|imp| imp.std_trait().is_none())
+        .filter_map(|imp| imp.trait_ty.as_ref())
+        .map(|ty| ty.rewrite())
+        .chain(has_compare(impls).then(|| format!("Comparable<{}>", ty.rewrite())))
+        .chain(
+            derives
+                .iter()
+                .any(|n| n == "Clone")
+                .then(|| "_Clone_".to_string()),
+        )
+        .chain(has_debug(impls, derives).then(|| "_Debug_".to_string()))
+        .unique()
+        .join(", ");
+
+    if traits.is_empty() {
+        "".to_string()
+    } else {
+        format!(" implements {}", traits)
+    }
+}
+
+/// `toString` for a class without `impl Display`, which prints `{}` like `{:?}`
+fn rewrite_format_fallback(ident: &str, impls: &[Impl], derives: &[String]) -> String {
+    let derived = derives.iter().any(|n| n == "Debug");
+    let implemented = impls.iter().any(|imp| imp.format_trait() == Some("Debug"));
+
+    if derived && implemented {
+        report(format!(
+            "`{}` both derives and implements `Debug`, remove one of them",
+            ident
+        ));
+    }
+
+    let has_display = impls
+        .iter()
+        .any(|imp| imp.format_trait() == Some("Display"));
+
+    match has_debug(impls, derives) && !has_display {
+        true => "
+		public String toString() {
+			return fmtDebug();
+		}
+		"
+        .to_string(),
+        false => "".to_string(),
+    }
+}
+
+/// `equals` and `compareTo` for `impl PartialEq` and `impl PartialOrd`, which call their methods
+fn rewrite_operator_bridges(ident: &str, ty: &Type, impls: &[Impl], derives: &[String]) -> String {
+    let mut rewritten = "".to_string();
+
+    for imp in impls {
+        let Some(method) = imp.std_method() else {
+            continue;
+        };
+
+        let std_trait = imp.std_trait().unwrap_or_default();
+
+        let Some(function) = imp.methods.iter().find(|f| f.ident == method) else {
+            report(format!(
+                "`impl {} for {}` needs `fn {}`",
+                std_trait, imp.ident, method
+            ));
+            continue;
+        };
+
+        match std_trait {
+            "PartialEq" if derives.iter().any(|n| n == "PartialEq" || n == "Eq") => {
+                report(format!(
+                    "`{}` both derives and implements `PartialEq`, remove one of them",
+                    imp.ident
+                ));
+            }
+            "PartialEq" => rewritten.push_str(&format!(
+                "
+				public boolean equals(Object other) {{
+					return other instanceof {} && eq(({}) other);
+				}}
+				",
+                ident,
+                ty.rewrite()
+            )),
+            "PartialOrd" | "Ord"
+                if function.returns.as_ref().map(|n| &n.which) != Some(&WhichType::Int) =>
+            {
+                report(format!(
+                    "`fn {}` in `impl {} for {}` must return `int`, negative, zero or positive like `compareTo`",
+                    method, std_trait, imp.ident
+                ));
+            }
+            // `Ord` wins over `PartialOrd` when both are implemented
+            "PartialOrd" if impls.iter().any(|n| n.std_trait() == Some("Ord")) => {}
+            "PartialOrd" | "Ord" => rewritten.push_str(&format!(
+                "
+				public int compareTo({} other) {{
+					return {}(other);
+				}}
+				",
+                ty.rewrite(),
+                rewrite_ident(method)
+            )),
+            _ => {}
+        }
+    }
+
+    rewritten
+}
+
+/// The associated constants of `impls`, as static fields of the class
+fn rewrite_impl_consts(impls: &[Impl]) -> String {
+    impls
+        .iter()
+        .flat_map(|imp| imp.consts.iter())
+        .map(|c| format!("{}\n", c.rewrite()))
+        .join("")
+}
+
+/// Check that each `impl<T> Stack<T>` names the type parameters of the type
+fn check_impl_params(ident: &str, generics: Option<&GenericArguments>, impls: &[Impl]) {
+    let params = generics.map(|n| n.args.clone()).unwrap_or_default();
+
+    for imp in impls {
+        let declared = imp
+            .generics
+            .as_ref()
+            .map(|n| n.args.clone())
+            .unwrap_or_default();
+
+        match &imp.ty_args {
+            Some(ty_args) if ty_args.args != params => {
+                report(format!(
+                    "`impl {}{}` must name the type parameters of `{}{}`",
+                    ident,
+                    ty_args.rewrite(None),
+                    ident,
+                    generics.map(|n| n.rewrite(None)).unwrap_or_default()
+                ));
+            }
+            Some(ty_args) => {
+                for arg in ty_args.args.iter().filter(|arg| !declared.contains(arg)) {
+                    report(format!(
+                        "Type parameter `{}` is not declared, write `impl<{}> {}<{}>`",
+                        arg,
+                        ty_args.args.join(", "),
+                        ident,
+                        ty_args.args.join(", ")
+                    ));
+                }
+            }
+            None if !declared.is_empty() => {
+                report(format!(
+                    "`impl<{}>` must be followed by `{}<{}>`",
+                    declared.join(", "),
+                    ident,
+                    declared.join(", ")
+                ));
+            }
+            None => {}
+        }
+    }
+}
+
+/// Check that each `impl Trait for X` names a trait of the program and has the methods
+/// the trait does not give a body
+fn check_trait_impls(impls: &[Impl]) {
+    for imp in impls.iter().filter(|imp| imp.std_trait().is_none()) {
+        let Some((trait_ident, _)) = imp.trait_ty.as_ref().and_then(|ty| ty.generic_parts()) else {
+            continue;
+        };
+
+        let Some(found) = scope::find_trait(&trait_ident) else {
+            report(format!(
+                "Cannot find trait `{}` in `impl {} for {}`",
+                trait_ident, trait_ident, imp.ident
+            ));
+            continue;
+        };
+
+        for method in found.methods.iter().filter(|n| n.body.is_none()) {
+            if !imp.methods.iter().any(|n| n.ident == method.ident) {
+                report(format!(
+                    "`impl {} for {}` is missing `fn {}`",
+                    trait_ident, imp.ident, method.ident
+                ));
+            }
+        }
+    }
+}
+
+/// The bounds `imp` puts on the type parameters, e.g. `T: Ord` of `impl<T: Ord> Stack<T>`,
+/// that the type itself does not have
+fn impl_only_bounds(imp: &Impl, type_bounds: &[WhereUnit]) -> Vec<WhereUnit> {
+    let declared = |generic: &str, constraint: &Type| {
+        type_bounds
+            .iter()
+            .any(|unit| unit.generic == generic && unit.constraints.contains(constraint))
+    };
+
+    imp.generics
+        .iter()
+        .flat_map(|n| n.bounds.clone())
+        .chain(imp.where_clause.clone().unwrap_or_default())
+        .filter_map(|mut unit| {
+            unit.constraints.retain(|n| !declared(&unit.generic, n));
+            (!unit.constraints.is_empty()).then_some(unit)
+        })
+        .collect()
+}
+
+/// The methods of `impls`, where static methods redeclare the type parameters of the class.
+/// java can only bound the type parameters of a class on the class, so the bounds an impl adds
+/// are only redeclared on its static methods.
+fn rewrite_impl_methods(
+    impls: Vec<Impl>,
+    generics: Option<&GenericArguments>,
+    where_clause: Option<&Vec<WhereUnit>>,
+) -> String {
+    let mut rewritten = "".to_string();
+    let type_bounds = generics
+        .map(|n| n.all_bounds(where_clause))
+        .unwrap_or_default();
+
+    for imp in impls {
+        let component = JasmineProgramComponent::Impl(imp.clone());
+        let added = impl_only_bounds(&imp, &type_bounds);
+        let bounds = [&type_bounds[..], &added[..]].concat();
+
+        for method in imp.methods.iter() {
+            let mut method = match imp.format_trait() {
+                Some(_) => imp.format_method(method),
+                None => method.clone(),
+            };
+
+            match (method.is_static, generics) {
+                (true, Some(generics)) => {
+                    let units = where_clause.into_iter().flatten().chain(&added).cloned();
+                    method.redeclare_generics(generics, Some(&units.collect()));
+                }
+                (false, _) if !added.is_empty() => report(format!(
+                    "Method `{}` can not use the bound `{}: {}` of its impl, declare it on `{}` or make `{}` a static method",
+                    method.ident,
+                    added[0].generic,
+                    added[0].constraints.iter().map(|n| n.describe()).join(" + "),
+                    imp.ident,
+                    method.ident
+                )),
+                _ => {}
+            }
+
+            rewritten.push_str(&scope::in_module_of(&component, || {
+                scope::with_bounds(bounds.clone(), || method.rewrite())
+            }));
+        }
+    }
+
+    rewritten
+}
+
 pub fn rewrite_struct_impl(structure: Structure, impls: Vec<Impl>) -> String {
     let mut rewritten = "".to_string();
 
+    check_impl_params(&structure.ident, structure.generics.as_ref(), &impls);
+    check_trait_impls(&impls);
+
     let ident = structure.ident.clone();
-    rewritten.push_str(&format!("public static class {ident}"));
+    rewritten.push_str(&format!(
+        "{}static class {ident}",
+        structure.visibility.rewrite()
+    ));
 
-    if let Some(generics) = structure.generics {
+    if let Some(generics) = &structure.generics {
         rewritten.push_str(&generics.rewrite(structure.where_clause.as_ref()));
     }
 
+    let ty = self_type(&structure.ident, structure.generics.as_ref());
+    rewritten.push_str(&rewrite_implements(&ty, &impls, &structure.derives));
     rewritten.push_str(" {\n");
 
-    rewritten.push_str(&Arg::rewrite_many((&structure.fields).clone(), ";\n"));
-    rewritten.push_str(";\n");
+    for field in structure.fields.iter() {
+        rewritten.push_str(&format!("{}\n", field.rewrite()));
+    }
+
+    // `Self` in the initializers and methods is the type being implemented
+    scope::push();
+    scope::declare(
+        "self",
+        self_type(&structure.ident, structure.generics.as_ref()),
+    );
+
+    rewritten.push_str(&rewrite_impl_consts(&impls));
 
     let constructor_args = structure
         .fields
@@ -145,12 +501,26 @@ pub fn rewrite_struct_impl(structure: Structure, impls: Vec<Impl>) -> String {
     );
 
     rewritten.push_str(&constructor);
+    rewritten.push_str(&structure.rewrite_derives());
+    rewritten.push_str(&rewrite_format_fallback(
+        &structure.ident,
+        &impls,
+        &structure.derives,
+    ));
+    rewritten.push_str(&rewrite_operator_bridges(
+        &structure.ident,
+        &ty,
+        &impls,
+        &structure.derives,
+    ));
 
-    for imp in impls {
-        for method in imp.methods {
-            rewritten.push_str(&method.rewrite());
-        }
-    }
+    rewritten.push_str(&rewrite_impl_methods(
+        impls,
+        structure.generics.as_ref(),
+        structure.where_clause.as_ref(),
+    ));
+
+    scope::pop();
 
     rewritten.push_str("}\n");
 
@@ -158,19 +528,111 @@ pub fn rewrite_struct_impl(structure: Structure, impls: Vec<Impl>) -> String {
 }
 
 fn rewrite_enum_impl(enu: Enumeration, impls: Vec<Impl>) -> String {
-    let mut rewritten = enu.rewrite_no_closing();
+    check_impl_params(&enu.ident, enu.generics.as_ref(), &impls);
+    check_trait_impls(&impls);
+
+    let ty = self_type(&enu.ident, enu.generics.as_ref());
+    let mut rewritten = enu.rewrite_no_closing(&rewrite_implements(&ty, &impls, &enu.derives));
+    rewritten.push_str(&enu.rewrite_derives());
+    rewritten.push_str(&rewrite_format_fallback(&enu.ident, &impls, &enu.derives));
+    rewritten.push_str(&rewrite_operator_bridges(
+        &enu.ident,
+        &ty,
+        &impls,
+        &enu.derives,
+    ));
 
-    for imp in impls {
-        for method in imp.methods {
-            rewritten.push_str(&method.rewrite());
-        }
-    }
+    // `Self` in the initializers and methods is the type being implemented
+    scope::push();
+    scope::declare("self", self_type(&enu.ident, enu.generics.as_ref()));
+
+    rewritten.push_str(&rewrite_impl_consts(&impls));
+    rewritten.push_str(&rewrite_impl_methods(
+        impls,
+        enu.generics.as_ref(),
+        enu.where_clause.as_ref(),
+    ));
+
+    scope::pop();
 
     rewritten.push_str("}\n");
 
     rewritten
 }
 
+fn rewrite_tuple(arity: usize) -> String {
+    let generics = (0..arity).map(|idx| format!("T{}", idx)).join(", ");
+
+    let fields = (0..arity)
+        .map(|idx| format!("public T{} _{};", idx, idx))
+        .join("\n");
+
+    let constructor_args = (0..arity)
+        .map(|idx| format!("T{} _{}", idx, idx))
+        .join(", ");
+
+    let constructor_values = (0..arity)
+        .map(|idx| format!("this._{} = _{};", idx, idx))
+        .join("\n");
+
+    let equals = (0..arity)
+        .map(|idx| format!("Objects.equals(this._{}, other._{})", idx, idx))
+        .join(" && ");
+
+    let items = (0..arity).map(|idx| format!("_{}", idx)).join(", ");
+
+    let cloned = (0..arity)
+        .map(|idx| format!("_clone_(this._{})", idx))
+        .join(", ");
+
+    let to_string = (0..arity)
+        .map(|idx| format!("this._{}", idx))
+        .join(" + \", \" + ");
+
+    let debug = (0..arity)
+        .map(|idx| format!("_debug_(this._{})", idx))
+        .join(" + \", \" + ");
+
+    format!(
+        "
+		public static class Tuple{arity}<{generics}> implements _Clone_, _Debug_ {{
+			{fields}
+
+			public Tuple{arity}({constructor_args}) {{
+				{constructor_values}
+			}}
+
+			public boolean equals(Object o) {{
+				if (!(o instanceof Tuple{arity})) {{
+					return false;
+				}}
+
+				Tuple{arity}<?{}> other = (Tuple{arity}<?{}>) o;
+				return {equals};
+			}}
+
+			public int hashCode() {{
+				return Objects.hash({items});
+			}}
+
+			public String toString() {{
+				return \"(\" + {to_string} + \")\";
+			}}
+
+			public String fmtDebug() {{
+				return \"(\" + {debug} + \")\";
+			}}
+
+			public Tuple{arity}<{generics}> clone() {{
+				return new Tuple{arity}<{generics}>({cloned});
+			}}
+		}}
+		",
+        ", ?".repeat(arity - 1),
+        ", ?".repeat(arity - 1),
+    )
+}
+
 fn rewrite_closure(ident: String, closure: ClosureTypeData) -> String {
     let mut rewritten = format!("public interface {ident}");
     let mut generics = vec![];
@@ -231,6 +693,7 @@ where
         "string" => return String::from("String"),
         "bool" => return String::from("booean"),
         "default" => return String::from("default_value"),
+        "new" => return String::from("_new"),
         _ => {}
     }
 
@@ -238,15 +701,24 @@ where
         return "this".to_string();
     }
 
+    // SCREAMING_SNAKE_CASE constants are named the same way in java
+    if ident.chars().any(|c| c.is_ascii_uppercase())
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    {
+        return ident;
+    }
+
     let mut words = ident.split('_');
     let mut new = words
         .next()
-        .map(|f| if f == "" { "_" } else { f })
+        .map(|f| if f.is_empty() { "_" } else { f })
         .map(ToString::to_string)
         .unwrap();
 
     for word in words {
-        if word == "" {
+        if word.is_empty() {
             new.push('_');
         } else {
             let l1upper = word.chars().next().unwrap().to_ascii_uppercase();
@@ -265,7 +737,13 @@ where
     new
 }
 
-pub fn rewrite(program: Vec<JasmineProgramComponent>, root_class: &String) -> String {
+pub fn rewrite(modules: Vec<ProgramModule>, root_class: &String) -> Result<String> {
+    // everything ends up in one java class
+    let program = modules
+        .iter()
+        .flat_map(|m| m.components.clone())
+        .collect_vec();
+
     let mut rewritten = format!(
         "
 		import java.util.*;
@@ -279,10 +757,54 @@ pub fn rewrite(program: Vec<JasmineProgramComponent>, root_class: &String) -> St
 
     rewritten.push_str(&mixin_builtins());
 
+    // `Vec::enumerate` in the builtins
+    add_tuple(2);
+
+    scope::set_program(&program, root_class);
+    scope::set_modules(&modules);
+
+    for var in program.iter().filter_map(|n| match n {
+        JasmineProgramComponent::Var(var) => Some(var),
+        JasmineProgramComponent::Static(s) => Some(&s.var),
+        _ => None,
+    }) {
+        match var.ty.clone().or_else(|| var.expr.infer_type()) {
+            Some(ty) => scope::declare(&var.ident, ty),
+            None => report(format!(
+                "Cannot infer the type of global `{}`, add a type annotation",
+                var.ident
+            )),
+        }
+    }
+
+    let constants = program
+        .iter()
+        .filter_map(|n| {
+            let JasmineProgramComponent::Const(c) = n else {
+                return None;
+            };
+            Some(c)
+        })
+        .collect_vec();
+
+    for c in constants.iter() {
+        scope::declare(&c.ident, c.ty.clone());
+    }
+
+    // constants come first, so java sees them before anything that uses them
+    for c in constants {
+        let component = JasmineProgramComponent::Const(c.clone());
+        rewritten.push_str(&scope::in_module_of(&component, || {
+            format!("{}\n", c.rewrite())
+        }));
+    }
+
     for structure in program
         .iter()
         .filter_map(|n| {
-            let JasmineProgramComponent::Struct(structure) = n else { return None };
+            let JasmineProgramComponent::Struct(structure) = n else {
+                return None;
+            };
             Some(structure)
         })
         .cloned()
@@ -290,7 +812,9 @@ pub fn rewrite(program: Vec<JasmineProgramComponent>, root_class: &String) -> St
         let impls = program
             .iter()
             .filter_map(|n| {
-                let JasmineProgramComponent::Impl(imp) = n else { return None };
+                let JasmineProgramComponent::Impl(imp) = n else {
+                    return None;
+                };
                 Some(imp)
             })
             .filter(|imp| imp.ident == structure.ident)
@@ -303,7 +827,9 @@ pub fn rewrite(program: Vec<JasmineProgramComponent>, root_class: &String) -> St
     for enu in program
         .iter()
         .filter_map(|n| {
-            let JasmineProgramComponent::Enum(enu) = n else { return None };
+            let JasmineProgramComponent::Enum(enu) = n else {
+                return None;
+            };
             Some(enu)
         })
         .cloned()
@@ -311,7 +837,9 @@ pub fn rewrite(program: Vec<JasmineProgramComponent>, root_class: &String) -> St
         let impls = program
             .iter()
             .filter_map(|n| {
-                let JasmineProgramComponent::Impl(imp) = n else { return None };
+                let JasmineProgramComponent::Impl(imp) = n else {
+                    return None;
+                };
                 Some(imp)
             })
             .filter(|imp| imp.ident == enu.ident)
@@ -322,15 +850,29 @@ pub fn rewrite(program: Vec<JasmineProgramComponent>, root_class: &String) -> St
     }
 
     for (structure, impls) in struct_impl_map {
-        rewritten.push_str(&format!("{}\n", rewrite_struct_impl(structure, impls)));
+        let component = JasmineProgramComponent::Struct(structure.clone());
+        rewritten.push_str(&scope::in_module_of(&component, || {
+            format!("{}\n", rewrite_struct_impl(structure, impls))
+        }));
     }
 
     for (enu, impls) in enum_impl_map {
-        rewritten.push_str(&format!("{}\n", rewrite_enum_impl(enu, impls)));
+        let component = JasmineProgramComponent::Enum(enu.clone());
+        rewritten.push_str(&scope::in_module_of(&component, || {
+            format!("{}\n", rewrite_enum_impl(enu, impls))
+        }));
+    }
+
+    for item in program.iter() {
+        let JasmineProgramComponent::Trait(tr) = item else {
+            continue;
+        };
+
+        rewritten.push_str(&scope::in_module_of(item, || format!("{}\n", tr.rewrite())));
     }
 
     for item in program {
-        match item {
+        scope::in_module_of(&item.clone(), || match item {
             JasmineProgramComponent::Fn(f) => {
                 if f.ident == "main" {
                     /* Main override */
@@ -344,26 +886,39 @@ pub fn rewrite(program: Vec<JasmineProgramComponent>, root_class: &String) -> St
             JasmineProgramComponent::Var(v) => {
                 rewritten.push_str(&format!("static {};", &v.rewrite()));
             }
+            JasmineProgramComponent::Static(s) => {
+                rewritten.push_str(&format!("{}\n", s.rewrite()));
+            }
             _ => {}
-        }
+        });
     }
 
-    for (ident, closure_type) in unsafe { CREATE_CLOSURES.clone().unwrap_or(HashMap::new()) } {
+    for (ident, closure_type) in CREATE_CLOSURES.with(|closures| closures.borrow().clone()) {
         rewritten.push_str(&rewrite_closure(ident, closure_type));
     }
 
-    rewritten.push_str("}");
+    for arity in CREATE_TUPLES.with(|tuples| tuples.borrow().clone()) {
+        rewritten.push_str(&rewrite_tuple(arity));
+    }
 
-    rewritten
+    rewritten.push('}');
+
+    let errors = ERRORS.with(|errors| errors.take());
+
+    if !errors.is_empty() {
+        bail!(errors.join("\n"));
+    }
+
+    Ok(rewritten)
 }
 
 fn mixin_builtins() -> String {
-    let mut lines = BUILTINS_JAVA.lines();
+    let lines = BUILTINS_JAVA.lines();
     let mut output_lines = vec![];
 
     let mut in_builtins = false;
 
-    while let Some(line) = lines.next() {
+    for line in lines {
         if line
             .trim()
             .to_lowercase()
@@ -0,0 +1,382 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::jasmine::*;
+
+thread_local! {
+    static PROGRAM: RefCell<Vec<JasmineProgramComponent>> = const { RefCell::new(vec![]) };
+    static ROOT_CLASS: RefCell<String> = const { RefCell::new(String::new()) };
+    static SCOPES: RefCell<Vec<HashMap<String, Type>>> = RefCell::new(vec![HashMap::new()]);
+    static ALIASES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    static RETURNS: RefCell<Vec<Option<Type>>> = const { RefCell::new(vec![]) };
+    static BOUNDS: RefCell<Vec<WhereUnit>> = const { RefCell::new(vec![]) };
+    static MODULES: RefCell<Vec<ProgramModule>> = const { RefCell::new(vec![]) };
+    static MODULE: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Make the top-level items visible to lookups made while rewriting
+pub fn set_program(program: &[JasmineProgramComponent], root_class: &str) {
+    PROGRAM.with(|p| *p.borrow_mut() = program.to_vec());
+    ROOT_CLASS.with(|r| *r.borrow_mut() = root_class.to_string());
+    SCOPES.with(|s| *s.borrow_mut() = vec![HashMap::new()]);
+}
+
+/// Make the modules of the program visible to `check_name`
+pub fn set_modules(modules: &[ProgramModule]) {
+    MODULES.with(|m| *m.borrow_mut() = modules.to_vec());
+}
+
+/// Rewrite `f` as part of the module defining `component`
+pub fn in_module_of<T>(component: &JasmineProgramComponent, f: impl FnOnce() -> T) -> T {
+    let module = MODULES.with(|m| {
+        m.borrow()
+            .iter()
+            .find(|m| m.components.contains(component))
+            .map(|m| m.path.clone())
+    });
+
+    let outer = MODULE.with(|m| m.replace(module));
+    let result = f();
+    MODULE.with(|m| *m.borrow_mut() = outer);
+
+    result
+}
+
+/// Report `ident`, used without a path, if it is an item of another module that is not imported
+/// into the module being rewritten. Locals shadow items.
+pub fn check_name(ident: &str) {
+    if SCOPES.with(|s| s.borrow().iter().skip(1).any(|n| n.contains_key(ident))) {
+        return;
+    }
+
+    let Some(module) = MODULE.with(|m| m.borrow().clone()) else {
+        return;
+    };
+
+    let error = MODULES.with(|m| path_error(&m.borrow(), &module, &[ident.to_string()]));
+
+    if let Some(error) = error {
+        rewrite::report(error);
+    }
+}
+
+/// The java class holding the top-level functions
+pub fn root_class() -> String {
+    ROOT_CLASS.with(|r| r.borrow().clone())
+}
+
+pub fn push() {
+    SCOPES.with(|s| s.borrow_mut().push(HashMap::new()));
+}
+
+pub fn pop() {
+    SCOPES.with(|s| {
+        s.borrow_mut().pop();
+    });
+}
+
+pub fn declare<S>(ident: S, ty: Type)
+where
+    S: ToString,
+{
+    SCOPES.with(|s| {
+        if let Some(scope) = s.borrow_mut().last_mut() {
+            scope.insert(ident.to_string(), ty);
+        }
+    });
+}
+
+pub fn lookup(ident: &str) -> Option<Type> {
+    SCOPES.with(|s| {
+        s.borrow()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(ident).cloned())
+    })
+}
+
+/// Rewrite `f` with each ident in `aliases` standing in for a java expression,
+/// e.g. match bindings used in a guard before they are declared
+pub fn with_aliases<T>(aliases: &[(String, String)], f: impl FnOnce() -> T) -> T {
+    let old = ALIASES.with(|a| {
+        let mut a = a.borrow_mut();
+        let old = a.clone();
+        a.extend(aliases.iter().cloned());
+        old
+    });
+
+    let result = f();
+
+    ALIASES.with(|a| *a.borrow_mut() = old);
+
+    result
+}
+
+pub fn alias(ident: &str) -> Option<String> {
+    ALIASES.with(|a| a.borrow().get(ident).cloned())
+}
+
+/// Rewrite the body of a function or closure `f` that returns `returns`
+pub fn with_returns<T>(returns: Option<Type>, f: impl FnOnce() -> T) -> T {
+    RETURNS.with(|r| r.borrow_mut().push(returns));
+    let result = f();
+    RETURNS.with(|r| r.borrow_mut().pop());
+
+    result
+}
+
+/// The return type of the function or closure being rewritten
+pub fn returns() -> Option<Type> {
+    RETURNS.with(|r| r.borrow().last().cloned().flatten())
+}
+
+/// Rewrite `f` inside a fn or impl whose type parameters have `bounds`
+pub fn with_bounds<T>(bounds: Vec<WhereUnit>, f: impl FnOnce() -> T) -> T {
+    let len = BOUNDS.with(|b| {
+        let mut b = b.borrow_mut();
+        let len = b.len();
+        b.extend(bounds);
+        len
+    });
+
+    let result = f();
+
+    BOUNDS.with(|b| b.borrow_mut().truncate(len));
+
+    result
+}
+
+/// The bounds on the type parameter `ident` of the fns and impls being rewritten
+pub fn bounds(ident: &str) -> Vec<Type> {
+    BOUNDS.with(|b| {
+        b.borrow()
+            .iter()
+            .filter(|unit| unit.generic == ident)
+            .flat_map(|unit| unit.constraints.clone())
+            .collect()
+    })
+}
+
+pub fn is_module(ident: &str) -> bool {
+    PROGRAM.with(|p| {
+        p.borrow()
+            .iter()
+            .any(|n| matches!(n, JasmineProgramComponent::Mod(m) if m.ident == ident))
+    })
+}
+
+pub fn find_struct(ident: &str) -> Option<Structure> {
+    PROGRAM.with(|p| {
+        p.borrow().iter().find_map(|n| match n {
+            JasmineProgramComponent::Struct(s) if s.ident == ident => Some(s.clone()),
+            _ => None,
+        })
+    })
+}
+
+pub fn find_enum(ident: &str) -> Option<Enumeration> {
+    PROGRAM.with(|p| {
+        p.borrow().iter().find_map(|n| match n {
+            JasmineProgramComponent::Enum(e) if e.ident == ident => Some(e.clone()),
+            _ => None,
+        })
+    })
+}
+
+/// Whether the struct or enum `ident` has `#[derive(derive)]`
+pub fn derives(ident: &str, derive: &str) -> bool {
+    let derives = match (find_struct(ident), find_enum(ident)) {
+        (Some(structure), _) => structure.derives,
+        (_, Some(enumeration)) => enumeration.derives,
+        _ => return false,
+    };
+
+    derives.iter().any(|n| n == derive)
+}
+
+/// Whether `ident` has `impl std_trait`, see `Impl::std_trait`
+pub fn implements_std(ident: &str, std_trait: &str) -> bool {
+    find_impls(ident)
+        .iter()
+        .any(|imp| imp.std_trait() == Some(std_trait))
+}
+
+pub fn find_trait(ident: &str) -> Option<Trait> {
+    PROGRAM.with(|p| {
+        p.borrow().iter().find_map(|n| match n {
+            JasmineProgramComponent::Trait(t) if t.ident == ident => Some(t.clone()),
+            _ => None,
+        })
+    })
+}
+
+pub fn find_const(ident: &str) -> Option<Constant> {
+    PROGRAM.with(|p| {
+        p.borrow().iter().find_map(|n| match n {
+            JasmineProgramComponent::Const(c) if c.ident == ident => Some(c.clone()),
+            _ => None,
+        })
+    })
+}
+
+/// Find an associated constant `ty_ident::ident` defined in an impl
+pub fn find_assoc_const(ty_ident: &str, ident: &str) -> Option<Constant> {
+    find_impls(ty_ident)
+        .into_iter()
+        .flat_map(|imp| imp.consts)
+        .find(|c| c.ident == ident)
+}
+
+/// `ident`, or the type of the impl being rewritten if it is `Self`
+pub fn resolve_self(ident: &str) -> String {
+    match ident {
+        "Self" => lookup("self")
+            .and_then(|ty| ty.generic_parts())
+            .map(|(ident, _)| ident)
+            .unwrap_or(ident.to_string()),
+        _ => ident.to_string(),
+    }
+}
+
+/// Report a private member of `ty_ident` used outside the module defining `ty_ident`.
+/// `member` names it in the error, e.g. "Field `x`"
+fn check_private(ty_ident: &str, member: String, visibility: Option<Visibility>) {
+    if visibility != Some(Visibility::Private) || resolve_self("Self") == ty_ident {
+        return;
+    }
+
+    let Some(from) = MODULE.with(|m| m.borrow().clone()) else {
+        return;
+    };
+
+    let Some(defined_in) = MODULES.with(|m| module_of(&m.borrow(), ty_ident)) else {
+        return;
+    };
+
+    // a module sees the private items of its parents, like rust
+    if from.starts_with(&defined_in) {
+        return;
+    }
+
+    rewrite::report(format!(
+        "{} of `{}` is private to `{}`, mark it `pub` to use it in `{}`",
+        member,
+        ty_ident,
+        display_path(&defined_in),
+        display_path(&from)
+    ));
+}
+
+pub fn check_field(ty_ident: &str, field: &str) {
+    let visibility = find_struct(ty_ident)
+        .and_then(|s| s.fields.into_iter().find(|f| f.ident == field))
+        .map(|f| f.visibility);
+
+    check_private(ty_ident, format!("Field `{}`", field), visibility);
+}
+
+pub fn check_method(ty_ident: &str, method: &str) {
+    let visibility = find_method(ty_ident, method).map(|f| f.visibility);
+
+    check_private(ty_ident, format!("Method `{}`", method), visibility);
+}
+
+pub fn check_const(ty_ident: &str, ident: &str) {
+    let visibility = find_assoc_const(ty_ident, ident).map(|c| c.visibility);
+
+    check_private(ty_ident, format!("Constant `{}`", ident), visibility);
+}
+
+pub fn find_fn(ident: &str) -> Option<Function> {
+    PROGRAM.with(|p| {
+        p.borrow().iter().find_map(|n| match n {
+            JasmineProgramComponent::Fn(f) if f.ident == ident => Some(f.clone()),
+            _ => None,
+        })
+    })
+}
+
+/// Find a method defined directly in an impl of `ty_ident`
+pub fn find_method(ty_ident: &str, method: &str) -> Option<ImplFunction> {
+    find_impls(ty_ident)
+        .into_iter()
+        .flat_map(|imp| imp.methods)
+        .find(|f| f.ident == method)
+}
+
+/// The type parameters of a struct or enum, e.g. `T` of `struct Stack<T>`
+pub fn type_params(ty_ident: &str) -> Vec<String> {
+    find_struct(ty_ident)
+        .and_then(|s| s.generics)
+        .or_else(|| find_enum(ty_ident).and_then(|e| e.generics))
+        .map(|g| g.args)
+        .unwrap_or_default()
+}
+
+/// The type parameters of the method itself, e.g. `U` of `fn map<U>(&self) -> Stack<U>`
+pub fn method_type_params(ty_ident: &str, method: &str) -> Vec<String> {
+    find_method(ty_ident, method)
+        .and_then(|f| f.generics)
+        .map(|g| g.args)
+        .unwrap_or_default()
+}
+
+pub fn find_impls(ident: &str) -> Vec<Impl> {
+    PROGRAM.with(|p| {
+        p.borrow()
+            .iter()
+            .filter_map(|n| match n {
+                JasmineProgramComponent::Impl(imp) if imp.ident == ident => Some(imp.clone()),
+                _ => None,
+            })
+            .collect()
+    })
+}
+
+/// Find the return type of a method on a user-defined struct, enum or trait.
+/// The outer `Option` is `None` if there is no such method.
+pub fn find_method_returns(ty_ident: &str, method: &str) -> Option<Option<Type>> {
+    let impls = find_impls(ty_ident);
+    let generics = find_struct(ty_ident)
+        .and_then(|s| s.generics)
+        .or_else(|| find_enum(ty_ident).and_then(|e| e.generics));
+
+    if let Some(f) = impls
+        .iter()
+        .flat_map(|imp| imp.methods.iter())
+        .find(|f| f.ident == method)
+    {
+        let self_ty = super::self_type(ty_ident, generics.as_ref());
+
+        return Some(
+            f.returns
+                .as_ref()
+                .map(|ty| ty.substitute(&["Self".to_string()], &[self_ty])),
+        );
+    }
+
+    // methods generated by `#[derive(...)]`
+    let derived = match method {
+        "clone" => "Clone",
+        "default" => "Default",
+        _ => "",
+    };
+
+    if method == "to_string" && implements_std(ty_ident, "Display") {
+        return Some(Some(Type::new(WhichType::String)));
+    }
+
+    if derives(ty_ident, derived) {
+        return Some(Some(super::self_type(ty_ident, generics.as_ref())));
+    }
+
+    // default methods of implemented traits, or the trait itself
+    impls
+        .iter()
+        .filter_map(|imp| imp.trait_ty.as_ref()?.generic_parts())
+        .map(|(outer, _)| outer)
+        .chain(std::iter::once(ty_ident.to_string()))
+        .filter_map(|ident| find_trait(&ident))
+        .flat_map(|tr| tr.methods)
+        .find(|f| f.ident == method)
+        .map(|f| f.returns)
+}
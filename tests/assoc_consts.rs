@@ -0,0 +1,71 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn associated_constants() {
+    assert_output(
+        "struct Circle {
+            pub radius: float
+        }
+        impl Circle {
+            pub const PI2: float = 6.28;
+            pub const UNIT: float = 1.0;
+            pub fn new(radius: float) -> Self {
+                return Self { radius: radius };
+            }
+            pub fn unit() -> Circle {
+                return Self::new(Self::UNIT);
+            }
+            pub fn circumference(&self) -> float {
+                return Self::PI2 * self.radius;
+            }
+        }
+        enum Level { Low, High }
+        impl Level {
+            pub const COUNT: int = 2;
+        }
+        fn main() {
+            let c = Circle::new(2.0);
+            let u = Circle::unit();
+            let limit = Level::COUNT + 1;
+            println(\"{} {} {} {}\", c.circumference(), u.circumference(), Circle::PI2, limit);
+        }",
+        "12.56 6.28 6.28 3",
+    );
+}
+
+#[test]
+fn constants_become_static_final_fields() {
+    let java = java(
+        "struct Circle { pub radius: float }
+        impl Circle {
+            pub const PI2: float = 6.28;
+            const HIDDEN: int = 1;
+        }
+        fn main() {}",
+    );
+
+    assert!(java.contains("public static final Double PI2 = 6.28;"));
+    assert!(java.contains("private static final Integer HIDDEN = 1;"));
+}
+
+#[test]
+fn constants_refer_to_self() {
+    assert_output(
+        "struct Circle { pub radius: float }
+        impl Circle {
+            pub const PI: float = 3.5;
+            pub const PI2: float = Self::PI * 2.0;
+        }
+        enum Level { Low, High }
+        impl Level {
+            const COUNT: int = 2;
+            pub const LAST: int = Self::COUNT - 1;
+        }
+        fn main() {
+            println(\"{} {}\", Circle::PI2, Level::LAST);
+        }",
+        "7.0 1",
+    );
+}
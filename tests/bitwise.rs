@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn bitwise_and_shift_operators() {
+    assert_output(
+        "fn main() {
+            let a = 12;
+            let b = 10;
+            println(\"{} {} {}\", a & b, a | b, a ^ b);
+            println(\"{} {}\", a << 2, a >> 1);
+            println(\"{}\", !a);
+            let t = true;
+            println(\"{}\", !t);
+            println(\"{}\", a & 4 == 4);
+            println(\"{}\", 1 + 2 << 3);
+            println(\"{}\", a | b & 3);
+            println(\"{}\", t & false | true);
+        }",
+        "8 14 6\n48 6\n-13\nfalse\ntrue\n24\n14\ntrue",
+    );
+}
+
+#[test]
+fn compound_assignments() {
+    assert_output(
+        "fn main() {
+            let mut h = 7;
+            h <<= 2;
+            h ^= 5;
+            h |= 64;
+            h &= 127;
+            h >>= 1;
+            println(\"{}\", h);
+            let mut hash = 0;
+            for c in [97, 98, 99] { hash = (hash << 5) ^ c; }
+            println(\"{}\", hash);
+        }",
+        "44\n100387",
+    );
+}
+
+#[test]
+fn not_is_bitwise_on_integers() {
+    let java = java(
+        "fn main() {
+            let a = 12;
+            let t = true;
+            let x = !a;
+            let y = !t;
+        }",
+    );
+
+    assert!(java.contains("x = ~a;"));
+    assert!(java.contains("y = !t;"));
+}
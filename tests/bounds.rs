@@ -0,0 +1,153 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn inline_and_where_bounds() {
+    assert_output(
+        "trait Named {
+            fn name(&self) -> string;
+        }
+        trait Ranked {
+            fn rank(&self) -> int;
+        }
+        struct City { pub label: string, pub size: int }
+        impl Named for City {
+            fn name(&self) -> string { return self.label; }
+        }
+        impl Ranked for City {
+            fn rank(&self) -> int { return self.size; }
+        }
+        fn max<T: Ord>(a: T, b: T) -> T {
+            if a.compareTo(b) >= 0 {
+                return a;
+            }
+            return b;
+        }
+        fn biggest_name<T>(items: Vec<T>) -> string where T: Ranked + Named {
+            let mut best = items.get(0).unwrap();
+            for item in items {
+                if item.rank() > best.rank() {
+                    best = item;
+                }
+            }
+            return best.name();
+        }
+        fn main() {
+            println(\"{}\", max(3, 9));
+            println(\"{}\", max(\"pear\", \"apple\"));
+            let mut cities: Vec<City> = Vec::new();
+            cities.push(City { label: \"Oslo\", size: 700 });
+            cities.push(City { label: \"Tokyo\", size: 14000 });
+            println(\"{}\", biggest_name(cities));
+        }",
+        "9\npear\nTokyo",
+    );
+}
+
+#[test]
+fn bounds_on_generic_types() {
+    let source = "struct SortedVec<T: Ord + Display> {
+            pub items: Vec<T>
+        }
+        impl<T: Ord + Display> SortedVec<T> {
+            pub fn new() -> SortedVec<T> {
+                return SortedVec { items: Vec::new() };
+            }
+            pub fn insert(&mut self, item: T) {
+                let mut idx = 0;
+                while idx < self.items.len() && self.items.get(idx).unwrap().compareTo(item) < 0 {
+                    idx += 1;
+                }
+                self.items.insert(idx, item);
+            }
+            pub fn first(&self) -> T {
+                return self.items.get(0).unwrap();
+            }
+        }
+        fn main() {
+            let mut s: SortedVec<int> = SortedVec::new();
+            s.insert(5);
+            s.insert(2);
+            s.insert(8);
+            println(\"{}\", s.first());
+        }";
+
+    let java = java(source);
+    assert!(java.contains("class SortedVec<T extends Comparable<T>>"));
+
+    assert_output(source, "2");
+}
+
+#[test]
+fn enum_constructors_keep_bounds_out_of_return_types() {
+    let source = "enum Best<T: Ord> {
+            One(T),
+            Nothing
+        }
+        impl<T: Ord> Best<T> {
+            pub fn pick(a: T, b: T) -> Best<T> {
+                if a.compareTo(b) > 0 {
+                    return Best::One(a);
+                }
+                return Best::One(b);
+            }
+        }
+        fn main() {
+            let b: Best<int> = Best::pick(3, 5);
+            match b {
+                Best::One(v) => { println(\"{}\", v); }
+                Best::Nothing => { println(\"none\"); }
+            }
+        }";
+
+    let java = java(source);
+    assert!(java.contains("public static <T extends Comparable<T>> Best<T> One(T data)"));
+
+    assert_output(source, "5");
+}
+
+#[test]
+fn impl_bounds_on_instance_methods() {
+    let errors = errors(
+        "struct Stack<T> { pub items: Vec<T> }
+        impl<T: Ord> Stack<T> {
+            pub fn largest(&self) -> T {
+                return self.items.get(0).unwrap();
+            }
+        }
+        fn main() {}",
+    );
+
+    assert!(errors.contains(
+        "Method `largest` can not use the bound `T: Ord` of its impl, declare it on `Stack` or make `largest` a static method"
+    ));
+}
+
+#[test]
+fn clone_and_default_bounds_are_dropped() {
+    let source = "trait Named {
+            fn name(&self) -> string;
+        }
+        struct City { pub label: string }
+        impl Named for City {
+            fn name(&self) -> string {
+                return self.label;
+            }
+        }
+        fn describe<T>(item: T) -> string where T: Named + Clone + Default {
+            return item.name();
+        }
+        fn first<T: Clone>(items: Vec<T>) -> T {
+            return items.get(0).unwrap();
+        }
+        fn main() {
+            println(\"{} {}\", describe(City { label: \"Oslo\" }), first([4, 5]));
+        }";
+
+    let java = java(source);
+    assert!(java.contains("<T extends Named> String describe(T item)"));
+    assert!(java.contains("<T> T first(Vec<T> items)"));
+
+    assert_output(source, "Oslo 4");
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn numeric_char_bool_and_enum_casts() {
+    assert_output(
+        "enum Color { Red, Green, Blue }
+        fn main() {
+            let x: int = 7;
+            let c: char = 'a';
+            let n: int = 66;
+            let flag: bool = true;
+            let f: float = 3.99;
+            let col: Color = Color::Blue;
+            println(\"{}\", x as float / 2.0);
+            println(\"{}\", c as int);
+            println(\"{}\", n as char);
+            println(\"{}\", flag as int);
+            println(\"{}\", f as int);
+            println(\"{}\", col as int);
+            println(\"{}\", (x + 1) as float);
+            println(\"{}\", 'z' as int as float);
+        }",
+        "3.5\n97\nB\n1\n3\n2\n8.0\n122.0",
+    );
+}
+
+#[test]
+fn casts_bind_tighter_than_arithmetic() {
+    assert_output(
+        "fn main() {
+            let v: int[] = [1, 2, 4];
+            let avg = v.sum_int() as float / v.len() as float;
+            println(\"{}\", avg);
+        }",
+        "2.3333333333333335",
+    );
+}
+
+#[test]
+fn invalid_casts() {
+    let errors = errors(
+        "struct P { pub x: int }
+        enum S { A(int), B }
+        fn main() {
+            let n: int = 3;
+            let f: float = 1.5;
+            let a = n as bool;
+            let b = n as P;
+            let c = S::A(3) as int;
+            let d = f as bool;
+            let t: bool = true;
+            let e = t as float;
+        }",
+    );
+
+    assert!(errors.contains("Cannot cast `int` as `bool`"));
+    assert!(errors.contains("Cannot cast `int` as `P`"));
+    assert!(errors.contains("Cannot cast `S` as `int`"));
+    assert!(errors.contains("Cannot cast `float` as `bool`"));
+    assert!(errors.contains("Cannot cast `bool` as `float`"));
+}
+
+#[test]
+fn parenthesized_casts() {
+    assert_output(
+        "fn main() {
+            let s: int = 7;
+            let n: int = 2;
+            let avg = (s as float) / (n as float);
+            println(\"{}\", avg);
+            println(\"{}\", (s as i64) * 3);
+        }",
+        "3.5\n21",
+    );
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn expression_closures_with_inferred_arguments() {
+    assert_output(
+        "fn apply(f: Closure<(int), int>, x: int) -> int {
+            return f.call(x);
+        }
+        fn main() {
+            let mut v = [5, 3, 8, 1, 4];
+            v.retain(|x| x % 2 == 0 || x > 4);
+            println(\"{}\", v.len());
+            let found = v.find(|x| x > 6);
+            println(\"{}\", found.unwrap());
+            let idx = v.find_index(|x| x == 4).unwrap_or(100);
+            println(\"{}\", idx);
+            let doubled = found.map(|x| x * 2).unwrap();
+            println(\"{}\", doubled);
+            println(\"{}\", apply(|x| x + 10, 5));
+            let add: Closure<(int, int), int> = |a, b| a + b;
+            println(\"{}\", add.call(2, 3));
+            let triple = |x: int| x * 3;
+            println(\"{}\", triple.call(4));
+            let fallback = v.get(99).unwrap_or_else(|| 42);
+            println(\"{}\", fallback);
+        }",
+        "3\n8\n2\n16\n15\n5\n12\n42",
+    );
+}
+
+#[test]
+fn block_and_match_bodies() {
+    assert_output(
+        "fn main() {
+            let old = |x: int| -> int {
+                return x - 1;
+            };
+            println(\"{}\", old.call(4));
+            let found = Option::Some(8);
+            let label = found.map(|x| match x {
+                8 => \"eight\",
+                _ => \"other\",
+            });
+            println(\"{}\", label.unwrap());
+        }",
+        "3\neight",
+    );
+}
+
+#[test]
+fn uninferable_closure() {
+    let errors = errors(
+        "fn main() {
+            let f = |x| x + 1;
+        }",
+    );
+
+    assert!(errors.contains("Cannot infer the type of closure `f`, add a type annotation"));
+}
+
+#[test]
+fn closures_over_tuples_and_vecs() {
+    assert_output(
+        "fn apply(f: Closure<((int, int)), int>, p: (int, int)) -> int {
+            return f.call(p);
+        }
+        fn total(f: Closure<(Vec<int>), int>, v: Vec<int>) -> int {
+            return f.call(v);
+        }
+        fn main() {
+            let pts: (int, int)[] = [(1, 2), (3, 4)];
+            let found = pts.find(|p| p.0 == 3).unwrap();
+            println(\"{} {} {}\", apply(|p| p.0 + p.1, (5, 6)), found.1, total(|v| v.len(), [1, 2, 3]));
+        }",
+        "11 4 3",
+    );
+}
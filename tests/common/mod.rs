@@ -0,0 +1,168 @@
+//! Compile jasmine programs with the built compiler, and run them with java, which must be installed
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// How long a compiled program may run before it is treated as hanging
+const RUN_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// An empty directory for one program, jasmine writes the java file where it runs
+fn scratch_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "jasmine-test-{}-{}",
+        std::process::id(),
+        NEXT_DIR.fetch_add(1, Ordering::SeqCst)
+    ));
+
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+/// Compile a program of several files, the first one is the root and is compiled to `Main.java`.
+/// `Err` holds what the compiler reported.
+fn compile_in(dir: &Path, files: &[(&str, &str)]) -> Result<String, String> {
+    for (path, source) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_jasmine"))
+        .arg(files[0].0)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+
+    match output.status.success() {
+        true => Ok(fs::read_to_string(dir.join("Main.java")).unwrap()),
+        false => Err(format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )),
+    }
+}
+
+fn has_java() -> bool {
+    Command::new("javac")
+        .arg("-version")
+        .output()
+        .is_ok_and(|n| n.status.success())
+}
+
+/// The java for a program of several files with each run of whitespace collapsed to one space,
+/// panicking with the compile errors if it fails
+pub fn java_files(files: &[(&str, &str)]) -> String {
+    compile_in(&scratch_dir(), files)
+        .unwrap_or_else(|errors| panic!("{}", errors))
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The collapsed java for `source`, panicking with the compile errors if it fails
+pub fn java(source: &str) -> String {
+    java_files(&[("main.jasmine", source)])
+}
+
+/// The compile errors of a program of several files, panicking if it compiles
+pub fn errors_files(files: &[(&str, &str)]) -> String {
+    match compile_in(&scratch_dir(), files) {
+        Ok(java) => panic!("expected compile errors, got:\n{}", java),
+        Err(errors) => errors,
+    }
+}
+
+/// The compile errors of `source`, panicking if it compiles
+pub fn errors(source: &str) -> String {
+    errors_files(&[("main.jasmine", source)])
+}
+
+/// Compile a program and run it with java, returning what it printed.
+/// Panics if no JDK is installed, so runtime tests can not pass without running.
+pub fn run_files(files: &[(&str, &str)]) -> String {
+    let dir = scratch_dir();
+    let java = compile_in(&dir, files).unwrap_or_else(|errors| panic!("{}", errors));
+
+    assert!(
+        has_java(),
+        "`javac` was not found, install a JDK to run the runtime tests"
+    );
+
+    let javac = Command::new("javac")
+        .args(["-nowarn", "Main.java"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+
+    assert!(
+        javac.status.success(),
+        "javac rejected the output:\n{}\n{}",
+        String::from_utf8_lossy(&javac.stderr),
+        java
+    );
+
+    let mut child = Command::new("java")
+        .arg("Main")
+        .current_dir(&dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let started = Instant::now();
+
+    while child.try_wait().unwrap().is_none() {
+        if started.elapsed() > RUN_TIMEOUT {
+            child.kill().unwrap();
+            panic!("the program did not finish in {:?}:\n{}", RUN_TIMEOUT, java);
+        }
+
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut stdout)
+        .unwrap();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+
+    assert!(
+        child.wait().unwrap().success(),
+        "the program failed:\n{}\n{}",
+        stderr,
+        java
+    );
+
+    stdout
+}
+
+/// Compile and run `source`, checking what it printed
+pub fn assert_output(source: &str, expected: &str) {
+    assert_output_files(&[("main.jasmine", source)], expected);
+}
+
+pub fn assert_output_files(files: &[(&str, &str)], expected: &str) {
+    assert_eq!(run_files(files).trim_end(), expected.trim_end());
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn if_and_match_values() {
+    assert_output(
+        "enum Shape { Circle(float), Square(float), Empty }
+        fn area(s: Shape) -> float {
+            let a = match s {
+                Shape::Circle(r) => 3.0 * r * r,
+                Shape::Square(w) => { let sq = w * w; sq },
+                Shape::Empty => 0.0,
+            };
+            return a;
+        }
+        fn main() {
+            let a = 3;
+            let b = 7;
+            let m: int = if a > b { a } else { b };
+            println(\"{}\", m);
+            let grade: char = match m {
+                90..=100 => 'A',
+                5..=89 => 'B',
+                _ => 'F',
+            };
+            println(\"{}\", grade);
+            let o = Option::Some(5);
+            let v = if let Option::Some(x: int) = o { x } else { 0 };
+            println(\"{}\", v);
+            let label = if m > 5 { let t = \"big\"; t } else if m > 2 { \"mid\" } else { \"small\" };
+            println(label);
+            println(\"{}\", area(Shape::Square(2.0)));
+            let w = match o { Option::Some(n) if n > 3 => n, Option::Some(n) => 0 - n, Option::None => 0 };
+            println(\"{}\", w);
+        }",
+        "7\nB\n5\nbig\n4.0\n5",
+    );
+}
+
+#[test]
+fn lets_in_arms_are_visible_to_inference() {
+    assert_output(
+        "fn main() {
+            let a = 4;
+            let c = true;
+            let big = if c { let t = a * 2; t } else { let u = a; u };
+            let label = match a { 4 => { let s = \"four\"; s }, _ => { let o = \"other\"; o } };
+            println(\"{} {}\", big, label);
+        }",
+        "8 four",
+    );
+}
+
+#[test]
+fn while_condition_is_checked_every_iteration() {
+    assert_output(
+        "fn main() {
+            let mut i = 0;
+            while if i < 3 { let t = i + 1; t < 3 } else { false } { i += 1; }
+            println(\"{}\", i);
+        }",
+        "2",
+    );
+}
+
+#[test]
+fn else_if_condition_runs_after_earlier_branches() {
+    assert_output(
+        "fn check(n: int) -> bool {
+            println(\"checked {}\", n);
+            return n > 0;
+        }
+        fn main() {
+            let x = 1;
+            if x == 1 {
+                println(\"one\");
+            } else if if x > 0 { let ok = check(x); ok } else { false } {
+                println(\"positive\");
+            } else {
+                println(\"other\");
+            }
+            let y = if x == 1 { 10 } else if if x > 0 { let ok = check(x); ok } else { false } { 20 } else { 30 };
+            println(\"{}\", y);
+        }",
+        "one\n10",
+    );
+}
+
+#[test]
+fn logical_rhs_is_short_circuited() {
+    assert_output(
+        "fn noisy(n: int) -> int {
+            println(\"noisy {}\", n);
+            return n;
+        }
+        fn main() {
+            let x = 0;
+            let a = x > 0 && noisy(if x > 1 { 1 } else { let t = noisy(1); t }) > 0;
+            let b = x == 0 || noisy(if x > 1 { 2 } else { let t = noisy(2); t }) > 0;
+            let c = x == 0 && noisy(if x == 0 { let t = noisy(3); t } else { 3 }) > 0;
+            println(\"{} {} {}\", a, b, c);
+        }",
+        "noisy 3\nnoisy 3\nfalse true true",
+    );
+}
+
+#[test]
+fn ternary_branches_only_run_when_taken() {
+    assert_output(
+        "fn check(n: int) -> bool {
+            println(\"checked {}\", n);
+            return n > 0;
+        }
+        fn main() {
+            let x = 1;
+            let y = if x == 1 { 1 } else { if check(x) { let t = 2; t } else { 3 } };
+            let z = match x { 1 => 1, _ => if check(x) { let t = 2; t } else { 3 } };
+            println(\"{} {}\", y, z);
+        }",
+        "1 1",
+    );
+}
+
+#[test]
+fn values_as_operands() {
+    assert_output(
+        "fn side(n: int) -> int {
+            println(\"side {}\", n);
+            return n;
+        }
+        fn main() {
+            let a = 2;
+            let c = false;
+            let x = side(1) + match a { 1 => 10, _ => { let t = side(2); t * 10 } };
+            let y = (if c { 1 } else { 2 }) * 10;
+            let z = if c { 1 } else { 2 } * 10;
+            let mut i = 0;
+            while i < 3 && match i { 2 => false, _ => true } {
+                i += 1;
+            }
+            println(\"{} {} {} {}\", x, y, z, i);
+        }",
+        "side 1\nside 2\n21 20 20 2",
+    );
+}
+
+#[test]
+fn uninferable_value() {
+    let errors = errors(
+        "fn main() {
+            let c = true;
+            let v = if c { let t = Vec::new(); t } else { Vec::new() };
+        }",
+    );
+
+    assert!(errors.contains("Cannot infer the type of this `if` expression, add a type annotation"));
+}
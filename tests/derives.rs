@@ -0,0 +1,170 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn derived_struct_methods() {
+    assert_output(
+        "#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+        struct Point {
+            pub x: int,
+            pub y: int
+        }
+        #[derive(Debug, Clone, PartialEq, Default)]
+        struct Player {
+            pub name: string,
+            pub pos: Point,
+            pub scores: Vec<int>,
+            pub best: Option<int>,
+            pub level: i8
+        }
+        fn main() {
+            let p = Point { x: 1, y: 2 };
+            let q = p.clone();
+            println(\"{:?}\", p);
+            println(\"{} {}\", p == q, p != q);
+            q.x = 5;
+            println(\"{:?} {:?} {}\", p, q, p == q);
+            let r = Point { x: 1, y: 2 };
+            println(\"{}\", p.hash_code() == r.hash_code());
+            println(\"{:?}\", Point::default());
+            let mut a = Player { name: \"ann\", pos: p, scores: [1, 2], best: Option::Some(2), level: 3 };
+            let b = a.clone();
+            a.scores.push(9);
+            a.pos.y = 7;
+            println(\"{:?}\", a);
+            println(\"{:?}\", b);
+            println(\"{}\", a == b);
+            println(\"{:?}\", Player::default());
+        }",
+        "Point { x: 1, y: 2 }
+true false
+Point { x: 1, y: 2 } Point { x: 5, y: 2 } false
+true
+Point { x: 0, y: 0 }
+Player { name: \"ann\", pos: Point { x: 1, y: 7 }, scores: [1, 2, 9], best: Some(2), level: 3 }
+Player { name: \"ann\", pos: Point { x: 1, y: 2 }, scores: [1, 2], best: Some(2), level: 3 }
+false
+Player { name: \"\", pos: Point { x: 0, y: 0 }, scores: [], best: None, level: 0 }",
+    );
+}
+
+#[test]
+fn derived_enum_and_generic_methods() {
+    assert_output(
+        "#[derive(Debug, Clone, PartialEq)]
+        struct Point { pub x: int, pub y: int }
+        #[derive(Debug, Clone, PartialEq, Hash, Default)]
+        enum Shape {
+            Circle(float),
+            Square(Point),
+            #[default]
+            Empty
+        }
+        #[derive(Debug, Clone, PartialEq)]
+        struct Wrapper<T> {
+            pub value: T
+        }
+        fn main() {
+            let s = Shape::Square(Point { x: 3, y: 4 });
+            let t = s.clone();
+            println(\"{:?} {}\", t, s == t);
+            println(\"{}\", s == Shape::Circle(1.5));
+            println(\"{:?}\", Shape::default());
+            let w: Wrapper<string> = Wrapper { value: \"hi\" };
+            println(\"{:?} {}\", w, w == w.clone());
+        }",
+        "Square(Point { x: 3, y: 4 }) true\nfalse\nEmpty\nWrapper { value: \"hi\" } true",
+    );
+}
+
+#[test]
+fn underivable_traits() {
+    let errors = errors(
+        "#[derive(Debug, Copy)]
+        struct A {
+            pub x: int
+        }
+        #[derive(Default)]
+        struct B<T> {
+            pub value: T
+        }
+        #[derive(Default)]
+        enum C {
+            One,
+            Two
+        }
+        fn main() {}",
+    );
+
+    assert!(errors.contains(
+        "Cannot derive `Copy` for `A`, expected one of Debug, Clone, PartialEq, Eq, Hash, Default"
+    ));
+    assert!(errors.contains(
+        "Cannot derive `Default` for `B`, field `value` of type `T` has no default value"
+    ));
+    assert!(errors
+        .contains("Cannot derive `Default` for `C`, mark a variant without data `#[default]`"));
+}
+
+#[test]
+fn derived_fields_must_implement_the_trait() {
+    let errors = errors(
+        "struct Counter { pub n: int }
+        #[derive(Clone, PartialEq)]
+        struct Holder {
+            pub counts: Vec<Counter>,
+            pub name: String
+        }
+        #[derive(Clone, Eq)]
+        enum Slot {
+            Full(Counter),
+            Empty
+        }
+        #[derive(Clone)]
+        struct Copied { pub inner: Holder, pub pair: (int, Slot) }
+        fn main() {}",
+    );
+
+    assert!(errors.contains(
+        "Cannot derive `Clone` for `Holder`, field `counts` of type `Vec<Counter>` does not implement `Clone`"
+    ));
+    assert!(errors.contains(
+        "Cannot derive `PartialEq` for `Holder`, field `counts` of type `Vec<Counter>` does not implement `PartialEq`"
+    ));
+    assert!(errors.contains(
+        "Cannot derive `Clone` for `Slot`, variant `Full` of type `Counter` does not implement `Clone`"
+    ));
+    assert!(errors.contains(
+        "Cannot derive `Eq` for `Slot`, variant `Full` of type `Counter` does not implement `PartialEq`"
+    ));
+    assert!(!errors.contains("field `name`"));
+    assert!(!errors.contains("for `Copied`"));
+}
+
+#[test]
+fn clone_on_strings_and_type_parameters() {
+    let source = "#[derive(Clone)]
+        struct Counter { pub n: int }
+        fn twice<T: Clone>(item: T) -> Vec<T> {
+            let mut items = Vec::new();
+            items.push(item.clone());
+            items.push(item);
+            return items;
+        }
+        fn main() {
+            let s = \"hi\";
+            let t = s.clone();
+            let c = Counter { n: 1 };
+            let cs = twice(c);
+            cs[0].n = 5;
+            let n = 3;
+            println(\"{} {} {} {}\", t, cs[0].n, cs[1].n, n.clone() + 1);
+        }";
+
+    let java = java(source);
+    assert!(java.contains("_clone_(s)"));
+    assert!(java.contains("_clone_(item)"));
+
+    assert_output(source, "hi 5 1 4");
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn let_and_for_patterns() {
+    assert_output(
+        "struct Point { pub x: int, pub y: int }
+        struct Line { pub a: Point, pub b: Point }
+        fn pair() -> (int, string) { return (7, \"seven\"); }
+        fn main() {
+            let (n, name) = pair();
+            println(\"{} {}\", n, name);
+            let p = Point { x: 3, y: 4 };
+            let Point { x, y } = p;
+            println(\"{} {}\", x, y);
+            let l = Line { a: Point { x: 1, y: 2 }, b: p };
+            let Line { a: Point { x: ax, y: _ }, b } = l;
+            println(\"{} {}\", ax, b.y);
+            let v = [10, 20, 30];
+            for (i, item) in v.enumerate() {
+                println(\"{}: {}\", i, item);
+            }
+            let pts = [Point { x: 5, y: 6 }];
+            for Point { x: px, y: py } in pts { println(\"{}\", px * py); }
+        }",
+        "7 seven\n3 4\n1 4\n0: 10\n1: 20\n2: 30\n30",
+    );
+}
+
+#[test]
+fn mut_applies_to_each_binding() {
+    let source = "struct Point { pub x: int, pub y: int }
+        fn main() {
+            let (mut c, d) = (1, 2.5);
+            c += 1;
+            let Point { mut x, y: mutable } = Point { x: 3, y: 4 };
+            x *= 2;
+            println(\"{} {} {} {}\", c, d, x, mutable);
+        }";
+
+    let java = java(source);
+    assert!(java.contains(" Integer c = "));
+    assert!(java.contains("final Double d = "));
+    assert!(java.contains(" Integer x = "));
+    assert!(java.contains("final Integer mutable = "));
+    assert!(!java.contains("final Integer c = "));
+    assert!(!java.contains("final Integer x = "));
+
+    assert_output(source, "2 2.5 6 4");
+}
+
+#[test]
+fn struct_pattern_must_match_the_type() {
+    let errors = errors(
+        "struct Point { pub x: int, pub y: int }
+        struct Other { pub x: int }
+        fn main() {
+            let point = Point { x: 1, y: 2 };
+            let Other { x } = point;
+        }",
+    );
+
+    assert!(errors.contains("Expected `Point` but found the pattern `Other { .. }`"));
+}
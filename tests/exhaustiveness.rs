@@ -0,0 +1,64 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn missing_variants() {
+    let errors = errors(
+        "enum Dir { North, East, South, West }
+        fn main() {
+            let d = Dir::North;
+            match d {
+                Dir::North => { println(\"n\"); }
+                Dir::South => { println(\"s\"); }
+            }
+        }",
+    );
+
+    assert!(errors.contains("Match is not exhaustive, missing `Dir::East`, `Dir::West`"));
+}
+
+#[test]
+fn unreachable_arms() {
+    let errors = errors(
+        "enum Dir { North, South }
+        fn main() {
+            let d = Dir::North;
+            match d {
+                _ => { println(\"any\"); }
+                Dir::South => { println(\"s\"); }
+            }
+        }",
+    );
+
+    assert!(
+        errors.contains("Unreachable match arm `Dir::South`, it is covered by the arms before it")
+    );
+}
+
+#[test]
+fn guards_do_not_cover_their_variant() {
+    let source = "enum Shape { Circle(float), Empty }
+        fn main() {
+            let s = Shape::Circle(2.0);
+            match s {
+                Shape::Circle(r) if r > 1.0 => { println(\"big\"); }
+                Shape::Empty => { println(\"empty\"); }
+            }
+        }";
+
+    assert!(errors(source).contains("Match is not exhaustive, missing `Shape::Circle(_)`"));
+
+    assert_output(
+        "enum Shape { Circle(float), Empty }
+        fn main() {
+            let s = Shape::Circle(0.5);
+            match s {
+                Shape::Circle(r) if r > 1.0 => { println(\"big\"); }
+                Shape::Circle(r) => { println(\"small\"); }
+                Shape::Empty => { println(\"empty\"); }
+            }
+        }",
+        "small",
+    );
+}
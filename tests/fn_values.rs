@@ -0,0 +1,85 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn functions_and_methods_as_values() {
+    assert_output(
+        "struct Circle {
+            pub radius: float
+        }
+        impl Circle {
+            pub fn new(radius: float) -> Circle {
+                return Circle { radius: radius };
+            }
+            pub fn is_big(&self) -> bool {
+                return self.radius > 2.0;
+            }
+        }
+        fn is_even(n: int) -> bool {
+            return n % 2 == 0;
+        }
+        fn negate(n: int) -> int {
+            return 0 - n;
+        }
+        fn twice(n: int) -> int {
+            return n * 2;
+        }
+        fn apply(f: Closure<(int), int>, x: int) -> int {
+            return f.call(x);
+        }
+        fn main() {
+            let mut v = [5, 2, 8, 1, 4];
+            v.retain(is_even);
+            println(\"{}\", v.len());
+            v.sort_by(negate);
+            println(\"{}\", v.get(0).unwrap());
+            println(\"{}\", apply(twice, 21));
+            let check = is_even;
+            println(\"{}\", check.call(3));
+            let circles = [Circle::new(1.0), Circle::new(3.0)];
+            let big = circles.find(Circle::is_big);
+            println(\"{}\", big.unwrap().radius);
+            let make = Circle::new;
+            println(\"{}\", make.call(5.0).radius);
+        }",
+        "3\n8\n42\nfalse\n3.0\n5.0",
+    );
+}
+
+#[test]
+fn generic_functions_and_methods_as_values() {
+    let source = "struct Wrapper<T> { pub value: T }
+        impl<T> Wrapper<T> {
+            pub fn get(&self) -> T { return self.value; }
+        }
+        fn id<T>(x: T) -> T { return x; }
+        fn main() {
+            let o = Option::Some(4);
+            let same = o.map(id);
+            println(\"{}\", same.unwrap());
+            let w = Option::Some(Wrapper { value: 3 });
+            let inner = w.map(Wrapper::get);
+            println(\"{}\", inner.unwrap());
+            let f: Closure<(int), int> = id;
+            println(\"{}\", f.call(9));
+        }";
+
+    let java = java(source);
+    assert!(java.contains("o.map(Main::id)"));
+    assert!(java.contains("w.map(Wrapper::get)"));
+
+    assert_output(source, "4\n3\n9");
+}
+
+#[test]
+fn generic_function_values_need_a_type() {
+    let errors = errors(
+        "fn id<T>(x: T) -> T { return x; }
+        fn main() {
+            let f = id;
+        }",
+    );
+
+    assert!(errors.contains("Cannot infer the type of closure `f`, add a type annotation"));
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn display_and_debug_impls() {
+    assert_output(
+        "#[derive(Debug, Clone)]
+        struct Money {
+            pub cents: int,
+            pub currency: string
+        }
+        impl Display for Money {
+            fn fmt(&self) -> string {
+                return format(\"{}.{} {}\", self.cents / 100, self.cents % 100, self.currency);
+            }
+        }
+        struct Card {
+            pub rank: int,
+            pub suit: char
+        }
+        impl Debug for Card {
+            fn fmt(&self) -> string {
+                return format(\"Card<{}{}>\", self.rank, self.suit);
+            }
+        }
+        impl Display for Card {
+            fn fmt(&self) -> string {
+                return format(\"{} of {}\", self.rank, self.suit);
+            }
+        }
+        #[derive(Debug)]
+        enum Coin {
+            Heads,
+            Tails(string)
+        }
+        fn main() {
+            let m = Money { cents: 1250, currency: \"EUR\" };
+            println(\"{}\", m);
+            println(\"{:?}\", m);
+            let s = m.to_string();
+            println(\"{} has {} chars\", s, s.length());
+            let c = Card { rank: 7, suit: 'h' };
+            println(\"{} / {:?}\", c, c);
+            let coins = [Coin::Heads, Coin::Tails(\"x\")];
+            println(\"{:?}\", coins);
+            println(\"{:?} {:?} {}\", \"quoted\", 'c', \"plain\");
+            print(\"100% {}\\n\", format(\"{:?}\", Option::Some(m)));
+        }",
+        "12.50 EUR
+Money { cents: 1250, currency: \"EUR\" }
+12.50 EUR has 9 chars
+7 of h / Card<7h>
+[Heads, Tails(\"x\")]
+\"quoted\" 'c' plain
+100% Some(Money { cents: 1250, currency: \"EUR\" })",
+    );
+}
+
+#[test]
+fn display_becomes_to_string() {
+    let java = java(
+        "struct Tag { pub label: string }
+        impl Display for Tag {
+            fn fmt(&self) -> string {
+                return self.label;
+            }
+        }
+        fn main() {}",
+    );
+
+    assert!(java.contains("public String toString()"));
+}
+
+#[test]
+fn format_impl_errors() {
+    let errors = errors(
+        "#[derive(Debug)]
+        struct A {
+            pub x: int
+        }
+        impl Debug for A {
+            fn fmt(&self) -> string {
+                return \"a\";
+            }
+        }
+        impl Display for A {
+            fn show(&self) -> string {
+                return \"a\";
+            }
+        }
+        fn main() {}",
+    );
+
+    assert!(errors.contains("`A` both derives and implements `Debug`, remove one of them"));
+    assert!(errors.contains(
+        "`impl Display for A` must only have `fn fmt(&self) -> string`, found `fn show`"
+    ));
+}
+
+#[test]
+fn debug_tuples() {
+    assert_output(
+        "#[derive(Debug)]
+        struct Slot { pub at: (int, string) }
+        fn main() {
+            let t = (1, \"a\", 'c');
+            println(\"{:?} {}\", t, t);
+            let s = Slot { at: (0, \"\") };
+            println(\"{:?}\", s);
+        }",
+        "(1, \"a\", 'c') (1, a, c)\nSlot { at: (0, \"\") }",
+    );
+}
+
+#[test]
+fn escaped_braces() {
+    assert_output(
+        "fn main() {
+            println(\"{{}} {}\", 1);
+            let s = format(\"{{{:?}}}\", \"a\");
+            print(\"{} }}{{\\n\", s);
+        }",
+        "{} 1\n{\"a\"} }{",
+    );
+}
+
+#[test]
+fn placeholders_must_match_arguments() {
+    let errors = errors(
+        "fn main() {
+            println(\"{} {}\", 1);
+            let s = format(\"{{}}\", 2);
+            print(\"{:?}\");
+            println(\"{} {:?}\", 1, 2);
+        }",
+    );
+
+    assert!(errors
+        .contains("The format string of `println` has 2 placeholders, but 1 argument was given"));
+    assert!(errors
+        .contains("The format string of `format` has 0 placeholders, but 1 argument was given"));
+    assert!(errors
+        .contains("The format string of `print` has 1 placeholder, but 0 arguments were given"));
+    assert_eq!(errors.matches("The format string").count(), 3);
+}
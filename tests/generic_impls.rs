@@ -0,0 +1,174 @@
+mod common;
+
+use common::*;
+
+const STACK: &str = "struct Stack<T> {
+    pub items: Vec<T>
+}
+impl<T> Stack<T> {
+    pub fn new() -> Stack<T> {
+        return Stack { items: Vec::new() };
+    }
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+    pub fn pop(&mut self) -> Option<T> {
+        return self.items.pop();
+    }
+    pub fn len(&self) -> int {
+        return self.items.len();
+    }
+    pub fn wrap(item: T) -> Stack<T> {
+        let mut s: Stack<T> = Stack::new();
+        s.push(item);
+        return s;
+    }
+}
+";
+
+#[test]
+fn generic_structs_and_enums() {
+    assert_output(
+        &format!(
+            "{}
+            struct Pair<A, B> {{
+                pub first: A,
+                pub second: B
+            }}
+            impl<A, B> Pair<A, B> {{
+                pub fn new(first: A, second: B) -> Self {{
+                    return Self {{ first: first, second: second }};
+                }}
+                pub fn first(&self) -> A {{
+                    return self.first;
+                }}
+                pub fn swap(&self) -> Pair<B, A> {{
+                    return Pair::new(self.second, self.first);
+                }}
+            }}
+            fn main() {{
+                let mut s: Stack<int> = Stack::new();
+                s.push(1);
+                s.push(2);
+                println(\"{{}}\", s.len());
+                println(\"{{}}\", s.pop().unwrap());
+                let w: Stack<string> = Stack::wrap(\"hi\");
+                println(\"{{}}\", w.len());
+                let p: Pair<int, string> = Pair::new(1, \"one\");
+                println(\"{{}}\", p.first());
+                let q = p.swap();
+                println(\"{{}}\", q.first());
+            }}",
+            STACK
+        ),
+        "2\n2\n1\n1\none",
+    );
+}
+
+#[test]
+fn static_methods_of_generic_types_are_inferred() {
+    let source = "enum Tree<T> { Leaf(T), Empty }
+        impl<T> Tree<T> {
+            pub fn leaf(value: T) -> Tree<T> { return Tree::Leaf(value); }
+            pub fn is_leaf(&self) -> bool {
+                return match self { Tree::Leaf(_) => { true } Tree::Empty => { false } };
+            }
+            pub fn me(&self) -> Self { return self; }
+            pub fn pick<U>(&self, other: U) -> U { return other; }
+        }
+        fn main() {
+            let t = Tree::leaf(3);
+            println(\"{}\", t.is_leaf());
+            let typed: Tree<int> = Tree::leaf(4);
+            let again = typed.me();
+            let s = typed.pick(\"x\");
+            println(\"{} {}\", again.is_leaf(), s);
+        }";
+
+    let java = java(source);
+    assert!(java.contains("final Tree<Integer> t = Tree.leaf(3);"));
+    assert!(java.contains("final Tree<Integer> again = typed.me();"));
+    assert!(java.contains("final var s = typed.pick(\"x\");"));
+
+    assert_output(source, "true\ntrue x");
+}
+
+#[test]
+fn impl_bounds_stay_on_their_methods() {
+    let source = format!(
+        "{}
+        impl<T: Ord> Stack<T> {{
+            pub fn max_of(a: T, b: T) -> T {{
+                if a > b {{
+                    return a;
+                }}
+                return b;
+            }}
+        }}
+        struct Plain {{ pub v: int }}
+        fn main() {{
+            let mut s: Stack<Plain> = Stack::new();
+            s.push(Plain {{ v: 1 }});
+            println(\"{{}} {{}}\", s.len(), Stack::max_of(3, 7));
+        }}",
+        STACK
+    );
+
+    let java = java(&source);
+    assert!(java.contains("class Stack<T>"));
+    assert!(java.contains("static <T extends Comparable<T>> T maxOf(T a, T b)"));
+
+    assert_output(&source, "1 7");
+}
+
+#[test]
+fn impl_must_name_type_parameters() {
+    let errors = errors(&format!(
+        "{}
+        impl Stack<T> {{
+            pub fn peek(&self) -> int {{ return 0; }}
+        }}
+        fn main() {{}}",
+        STACK
+    ));
+
+    assert!(errors.contains("Type parameter `T` is not declared, write `impl<T> Stack<T>`"));
+}
+
+#[test]
+fn type_arguments_are_inferred_from_later_calls() {
+    let source = "struct Stack<T> { pub items: Vec<T> }
+        impl<T> Stack<T> {
+            pub fn new() -> Stack<T> { return Stack { items: Vec::new() }; }
+            pub fn push(&mut self, item: T) { self.items.push(item); }
+            pub fn peek(&self) -> T { return self.items[self.items.len() - 1]; }
+        }
+        enum Tree<T> { Leaf(T), Empty }
+        fn main() {
+            let mut s = Stack::new();
+            s.push(1);
+            let top = s.peek() + 10;
+            let t = Tree::Leaf(\"x\");
+            println(\"{} {}\", top, match t { Tree::Leaf(v) => { v } Tree::Empty => { \"\" } });
+        }";
+
+    let java = java(source);
+    assert!(java.contains("Stack<Integer> s = Stack._new();"));
+    assert!(java.contains("final Tree<String> t = "));
+
+    assert_output(source, "11 x");
+}
+
+#[test]
+fn uninferable_type_arguments_need_an_annotation() {
+    let errors = errors(&format!(
+        "{}
+        fn main() {{
+            let s = Stack::new();
+            println(\"{{}}\", s.len());
+        }}",
+        STACK
+    ));
+
+    assert!(errors.contains("Cannot infer the type of `s`, add a type annotation"));
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn let_bindings_take_the_type_of_their_value() {
+    let java = java(
+        "struct Point { pub x: int, pub y: int }
+        impl Point {
+            pub fn new(x: int, y: int) -> Point { return Point { x: x, y: y }; }
+        }
+        fn half(x: float) -> float { return x / 2.0; }
+        fn main() {
+            let x = 5;
+            let name = \"jasmine\";
+            let p = Point::new(1, 2);
+            let h = half(3.0);
+            let b = x > 2;
+        }",
+    );
+
+    assert!(java.contains("final Integer x = 5;"));
+    assert!(java.contains("final String name ="));
+    assert!(java.contains("final Point p ="));
+    assert!(java.contains("final Double h ="));
+    assert!(java.contains("final Boolean b ="));
+}
+
+#[test]
+fn for_loop_variables() {
+    assert_output(
+        "let LIMIT = 3;
+        fn main() {
+            let mut total = 0.0;
+            let items = [1, 2, 3];
+            let grid = [[1, 2], [3, 4]];
+            for item in items { total += item; }
+            for row in grid { for cell in row { println(\"cell {}\", cell); } }
+            for i in 0..LIMIT { println(\"i {}\", i); }
+            let first = items.get(0).unwrap();
+            println(\"{} {}\", total, first);
+        }",
+        "cell 1\ncell 2\ncell 3\ncell 4\ni 0\ni 1\ni 2\n6.0 1",
+    );
+}
+
+#[test]
+fn uninferable_global() {
+    let errors = errors(
+        "let EMPTY = Vec::new();
+        fn main() {}",
+    );
+
+    assert!(errors.contains("Cannot infer the type of global `EMPTY`, add a type annotation"));
+}
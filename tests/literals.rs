@@ -0,0 +1,58 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn number_literal_forms() {
+    assert_output(
+        "fn main() {
+            println(\"{} {} {}\", 0xFF, 0b1010, 0o17);
+            println(\"{} {}\", 1e-9, 2.5E3);
+            println(\"{}\", 10i64 * 1000000000i64);
+            println(\"{}\", 3.0f32);
+            println(\"{} {}\", 1_000_000, 12i);
+            println(\"{}\", 2f);
+            println(\"{}\", 3. + 1.5);
+            for i in 0x2..0x5 { println(\"{}\", i); }
+        }",
+        "255 10 15\n1.0E-9 2500.0\n10000000000\n3.0\n1000000 12\n2.0\n4.5\n2\n3\n4",
+    );
+}
+
+#[test]
+fn out_of_range_literals() {
+    let errors = errors(
+        "fn main() {
+            let x: i8 = 300;
+        }",
+    );
+
+    assert!(errors.contains("Literal `300` is out of range for `i8`"));
+}
+
+#[test]
+fn negative_literals() {
+    assert_output(
+        "fn main() {
+            let x: i8 = -128;
+            let big = -9223372036854775808i64;
+            let v = [1, -1];
+            let y = -3.9 as int;
+            let z = 5 - -2;
+            let s = match y { -3 => \"minus three\", _ => \"other\" };
+            println(\"{} {} {} {} {} {}\", x, big, v[1], y, z, s);
+        }",
+        "-128 -9223372036854775808 -1 -3 7 minus three",
+    );
+}
+
+#[test]
+fn negative_literals_out_of_range() {
+    let errors = errors(
+        "fn main() {
+            let x: i8 = -129;
+        }",
+    );
+
+    assert!(errors.contains("Literal `-129` is out of range for `i8`"));
+}
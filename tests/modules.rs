@@ -0,0 +1,174 @@
+mod common;
+
+use common::*;
+
+const GEOMETRY: &str = "mod shapes;
+pub struct Point { pub x: float, pub y: float }
+pub fn dist(a: Point, b: Point) -> float {
+    let dx = a.x - b.x;
+    let dy = a.y - b.y;
+    return Math.sqrt(dx * dx + dy * dy);
+}";
+
+const SHAPES: &str = "use super::Point;
+pub struct Circle { pub center: Point, pub r: float }
+pub fn area(c: Circle) -> float { return c.r * c.r * 3.0; }";
+
+const SECRET: &str = "fn secret() -> int { return 42; }";
+
+#[test]
+fn mod_and_use_across_files() {
+    assert_output_files(
+        &[
+            (
+                "main.jasmine",
+                "mod geometry;
+                mod util;
+                use geometry::Point;
+                use geometry::shapes::{Circle, area};
+                use util::*;
+                fn main() {
+                    let p = Point { x: 1.0, y: 2.0 };
+                    let c = Circle { center: p, r: 2.0 };
+                    println(\"{}\", area(c));
+                    println(\"{}\", geometry::dist(p, Point { x: 4.0, y: 6.0 }));
+                    shout(\"hi\");
+                }",
+            ),
+            ("geometry/mod.jasmine", GEOMETRY),
+            ("geometry/shapes.jasmine", SHAPES),
+            (
+                "util.jasmine",
+                "pub fn shout(s: string) { println(\"{}!\", s); }",
+            ),
+        ],
+        "12.0\n5.0\nhi!",
+    );
+}
+
+#[test]
+fn missing_module_file() {
+    let errors = errors(
+        "mod geometry;
+        fn main() {}",
+    );
+
+    assert!(errors.contains("File not found for module `geometry`"));
+}
+
+#[test]
+fn names_are_unique_across_modules() {
+    let errors = errors_files(&[
+        (
+            "main.jasmine",
+            "mod geometry;
+            struct Point { x: float }
+            fn main() {}",
+        ),
+        ("geometry/mod.jasmine", GEOMETRY),
+        ("geometry/shapes.jasmine", SHAPES),
+    ]);
+
+    assert!(errors.contains("`Point` is defined in both `crate` and `geometry`"));
+}
+
+#[test]
+fn paths_through_modules() {
+    assert_output_files(
+        &[
+            (
+                "main.jasmine",
+                "mod geometry;
+                use geometry::shapes;
+                fn main() {
+                    let p: geometry::Point = geometry::Point { x: 1.0, y: 2.0 };
+                    let c = geometry::shapes::Circle { center: p, r: 1.0 };
+                    println(\"{}\", geometry::shapes::area(c));
+                    println(\"{}\", shapes::area(shapes::Circle { center: p, r: 2.0 }));
+                    println(\"{}\", crate::geometry::dist(p, p));
+                }",
+            ),
+            ("geometry/mod.jasmine", GEOMETRY),
+            ("geometry/shapes.jasmine", SHAPES),
+        ],
+        "3.0\n12.0\n0.0",
+    );
+}
+
+#[test]
+fn names_must_be_imported() {
+    let errors = errors_files(&[
+        (
+            "main.jasmine",
+            "mod geometry;
+            fn main() {
+                let p: Point = geometry::Point { x: 1.0, y: 2.0 };
+                println(\"{}\", dist(p, p));
+            }",
+        ),
+        ("geometry/mod.jasmine", GEOMETRY),
+        ("geometry/shapes.jasmine", SHAPES),
+    ]);
+
+    assert!(
+        errors.contains("Cannot find `Point` in `crate`, import it with `use geometry::Point;`")
+    );
+}
+
+#[test]
+fn unimported_functions() {
+    let errors = errors_files(&[
+        (
+            "main.jasmine",
+            "mod util;
+            fn main() {
+                let shout = 3;
+                println(\"{}\", shout);
+                whisper(\"hi\");
+            }",
+        ),
+        (
+            "util.jasmine",
+            "pub fn shout(s: string) { println(\"{}!\", s); }
+            pub fn whisper(s: string) { println(\"{}...\", s); }",
+        ),
+    ]);
+
+    assert!(
+        errors.contains("Cannot find `whisper` in `crate`, import it with `use util::whisper;`")
+    );
+    assert!(!errors.contains("`shout`"));
+}
+
+#[test]
+fn private_items_through_paths() {
+    let errors = errors_files(&[
+        (
+            "main.jasmine",
+            "mod geometry;
+            fn main() {
+                println(\"{}\", geometry::shapes::secret());
+            }",
+        ),
+        ("geometry/mod.jasmine", GEOMETRY),
+        ("geometry/shapes.jasmine", SECRET),
+    ]);
+
+    assert!(errors
+        .contains("`secret` is private to `geometry::shapes`, mark it `pub` to use it in `crate`"));
+
+    let errors = errors_files(&[
+        (
+            "main.jasmine",
+            "mod geometry;
+            fn main() {
+                println(\"{}\", secret());
+            }",
+        ),
+        ("geometry/mod.jasmine", GEOMETRY),
+        ("geometry/shapes.jasmine", SECRET),
+    ]);
+
+    assert!(errors
+        .contains("`secret` is private to `geometry::shapes`, mark it `pub` to use it in `crate`"));
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use common::*;
+
+const VECTOR: &str = "#[derive(Debug, Clone)]
+struct Vector2 {
+    pub x: float,
+    pub y: float
+}
+impl Add for Vector2 {
+    fn add(self, other: Vector2) -> Vector2 {
+        return Vector2 { x: self.x + other.x, y: self.y + other.y };
+    }
+}
+impl Sub for Vector2 {
+    fn sub(self, other: Vector2) -> Vector2 {
+        return Vector2 { x: self.x - other.x, y: self.y - other.y };
+    }
+}
+impl Mul<float> for Vector2 {
+    fn mul(self, k: float) -> Vector2 {
+        return Vector2 { x: self.x * k, y: self.y * k };
+    }
+}
+impl Neg for Vector2 {
+    fn neg(self) -> Vector2 {
+        return Vector2 { x: -self.x, y: -self.y };
+    }
+}
+impl PartialEq for Vector2 {
+    fn eq(&self, other: Vector2) -> bool {
+        return self.x - other.x == 0.0 && self.y - other.y == 0.0;
+    }
+}
+";
+
+#[test]
+fn arithmetic_and_equality_operators() {
+    assert_output(
+        &format!(
+            "{VECTOR}
+            fn main() {{
+                let a = Vector2 {{ x: 1.0, y: 2.0 }};
+                let b = Vector2 {{ x: 3.0, y: 4.0 }};
+                let c = a + b * 2;
+                println(\"{{:?}}\", c);
+                let d = c - a;
+                println(\"{{:?}}\", -d);
+                println(\"{{}} {{}}\", a + b - b == a, a != b);
+            }}"
+        ),
+        "Vector2 { x: 7.0, y: 10.0 }
+Vector2 { x: -6.0, y: -8.0 }
+true true",
+    );
+}
+
+#[test]
+fn ordering_operators() {
+    assert_output(
+        "#[derive(Debug)]
+        struct Fraction {
+            pub num: int,
+            pub den: int
+        }
+        impl PartialOrd for Fraction {
+            fn partial_cmp(&self, other: Fraction) -> int {
+                return self.num * other.den - other.num * self.den;
+            }
+        }
+        fn max<T: Ord>(a: T, b: T) -> T {
+            if a > b {
+                return a;
+            }
+            return b;
+        }
+        fn main() {
+            let half = Fraction { num: 1, den: 2 };
+            let third = Fraction { num: 1, den: 3 };
+            println(\"{} {}\", half > third, half <= third);
+            println(\"{:?}\", max(half, third));
+            println(\"{}\", max(3, 5));
+            let s = \"ab\";
+            println(\"{} {}\", s == \"ab\", s < \"b\");
+        }",
+        "true false
+Fraction { num: 1, den: 2 }
+5
+true true",
+    );
+}
+
+#[test]
+fn equality_on_bounded_type_parameters() {
+    let source = "struct Id {
+            pub n: int
+        }
+        impl PartialEq for Id {
+            fn eq(&self, other: Id) -> bool {
+                return self.n == other.n;
+            }
+        }
+        fn same<T: PartialEq>(a: T, b: T) -> bool {
+            return a == b;
+        }
+        fn differ<T>(a: T, b: T) -> bool where T: Eq {
+            return a != b;
+        }
+        fn main() {
+            println(\"{} {}\", same(Id { n: 1 }, Id { n: 1 }), differ(Id { n: 1 }, Id { n: 2 }));
+            println(\"{} {}\", same(\"a\" + \"b\", \"ab\"), differ(1000, 1000));
+        }";
+
+    let java = java(source);
+    assert!(java.contains("return Objects.equals(a, b);"));
+    assert!(java.contains("return !Objects.equals(a, b);"));
+
+    assert_output(source, "true true\ntrue false");
+}
+
+#[test]
+fn compound_assignment_operators() {
+    let source = format!(
+        "{VECTOR}
+        struct Body {{
+            pub pos: Vector2
+        }}
+        fn main() {{
+            let mut a = Vector2 {{ x: 1.0, y: 1.0 }};
+            a += Vector2 {{ x: 1.0, y: 2.0 }};
+            a *= 3;
+            a -= Vector2 {{ x: 1.0, y: 1.0 }};
+            println(\"{{:?}}\", a);
+            let mut body = Body {{ pos: a }};
+            body.pos += a;
+            println(\"{{:?}}\", body.pos);
+            let mut v = [a, a];
+            v[1] += Vector2 {{ x: 0.5, y: 0.5 }};
+            println(\"{{:?}}\", v);
+        }}"
+    );
+
+    let java = java(&source);
+    assert!(java.contains("a = a.add("));
+    assert!(java.contains("a = a.mul(3"));
+    assert!(java.contains("body.pos = body.pos.add(a);"));
+    assert!(java.contains(".unwrap().add(new Vector2(0.5, 0.5))"));
+
+    assert_output(
+        &source,
+        "Vector2 { x: 5.0, y: 8.0 }
+Vector2 { x: 10.0, y: 16.0 }
+[Vector2 { x: 5.0, y: 8.0 }, Vector2 { x: 5.5, y: 8.5 }]",
+    );
+}
+
+#[test]
+fn equality_on_numbers_compares_values() {
+    assert_output(
+        "fn big() -> int {
+            return 1000;
+        }
+        fn main() {
+            let a = big();
+            let b = big();
+            let x = 2.5;
+            let y = 2.5;
+            let l: i64 = 1000;
+            println(\"{} {} {} {}\", a == b, x == y, l == a, a != b + 1);
+        }",
+        "true true true true",
+    );
+}
+
+#[test]
+fn equality_on_tuples_and_objects() {
+    assert_output(
+        "#[derive(Clone, PartialEq)]
+        struct Box2 { pub v: int }
+        fn pair() -> (int, string) {
+            return (1000, \"a\");
+        }
+        fn main() {
+            let by = Box2 { v: 2 };
+            let t = (1, 2) == (1, 2);
+            println(\"{} {} {} {}\", t, pair() == pair(), by == by.clone(), pair() != (1000, \"b\"));
+        }",
+        "true true true true",
+    );
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn literal_range_or_binding_and_guard_patterns() {
+    assert_output(
+        "fn main() {
+            for n in 0..12 {
+                match n {
+                    0 => { println(\"zero\"); }
+                    1 | 2 | 3 => { println(\"small {}\", n); }
+                    x @ 4..=6 => { println(\"mid {}\", x); }
+                    x if x > 9 => { println(\"big {}\", x); }
+                    _ => { println(\"other\"); }
+                }
+            }
+            let c = 'q';
+            match c {
+                'a'..='m' => { println(\"first half\"); }
+                'q' | 'x' => { println(\"q or x\"); }
+                _ => { println(\"rest\"); }
+            }
+            let cmd = \"quit\";
+            match cmd {
+                \"go\" => { println(\"going\"); },
+                other => { println(\"unknown {}\", other); },
+            }
+            let m = 0 - 3;
+            match m { -3 => { println(\"neg\"); } _ => {} }
+        }",
+        "zero\nsmall 1\nsmall 2\nsmall 3\nmid 4\nmid 5\nmid 6\nother\nother\nother\nbig 10\nbig 11\n\
+         q or x\nunknown quit\nneg",
+    );
+}
+
+#[test]
+fn nested_variant_patterns() {
+    assert_output(
+        "enum Shape { Circle(float), Square(float), Empty }
+        fn describe(o: Option<Shape>) -> string {
+            match o {
+                Option::Some(Shape::Circle(r)) if r > 10.0 => { return \"big circle\"; }
+                Option::Some(Shape::Circle(r)) => { return format(\"circle {}\", r); }
+                Option::Some(s @ Shape::Square(_)) => { return \"square\"; }
+                Option::Some(Shape::Empty) => { return \"empty\"; }
+                Option::None => { return \"nothing\"; }
+            }
+        }
+        fn main() {
+            println(describe(Option::Some(Shape::Circle(12.0))));
+            println(describe(Option::Some(Shape::Circle(2.0))));
+            println(describe(Option::Some(Shape::Square(2.0))));
+            println(describe(Option::Some(Shape::Empty)));
+            println(describe(Option::None));
+        }",
+        "big circle\ncircle 2.0\nsquare\nempty\nnothing",
+    );
+}
+
+#[test]
+fn bindings_inside_or_patterns() {
+    let errors = errors(
+        "fn main() {
+            let n = 3;
+            match n {
+                x @ 1 | 2 => { println(\"{}\", x); }
+                _ => {}
+            }
+        }",
+    );
+
+    assert!(errors.contains("Bindings are not supported inside `|` patterns"));
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn assign_to_fields() {
+    assert_output(
+        "struct Pos { pub x: int, pub y: int }
+        struct Player { pub pos: Pos, pub count: int }
+        impl Player {
+            pub fn bump(&self) { self.count += 1; self.pos.x = self.pos.x + 10; }
+        }
+        fn main() {
+            let p = Player { pos: Pos { x: 1, y: 2 }, count: 0 };
+            p.bump();
+            p.bump();
+            p.pos.y = 3;
+            println(\"{} {} {}\", p.count, p.pos.x, p.pos.y);
+        }",
+        "2 21 3",
+    );
+}
+
+#[test]
+fn assign_to_vec_elements() {
+    assert_output(
+        "struct Pos { pub x: int, pub y: int }
+        fn main() {
+            let grid = [['.', '.'], ['.', '.']];
+            grid[1][0] = 'x';
+            println(\"{}{}{}{}\", grid[0][0], grid[0][1], grid[1][0], grid[1][1]);
+            let v = [1, 2, 3];
+            v[2] *= 2 + 3;
+            v[0] <<= 2;
+            println(\"{} {} {}\", v[0], v[1], v[2]);
+            let pts = [Pos { x: 0, y: 0 }];
+            pts[0].x = 9;
+            println(\"{}\", pts[0].x);
+        }",
+        "..x.\n4 2 15\n9",
+    );
+}
+
+#[test]
+fn vec_elements_are_set() {
+    let java = java(
+        "fn main() {
+            let v = [1, 2, 3];
+            v[2] = 7;
+        }",
+    );
+
+    assert!(java.contains("v.set(2, 7);"));
+}
+
+#[test]
+fn compound_assignment_reads_place_once() {
+    assert_output(
+        "fn idx(n: int) -> int {
+            println(\"idx {}\", n);
+            return n;
+        }
+        fn main() {
+            let v = [1, 2, 3];
+            v[idx(1)] += 5;
+            let grid = [[1, 2], [3, 4]];
+            grid[idx(0)][idx(1)] *= 10;
+            println(\"{} {}\", v[1], grid[0][1]);
+        }",
+        "idx 1\nidx 0\nidx 1\n7 20",
+    );
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn operators_bind_by_precedence() {
+    assert_output(
+        "fn main() {
+            let a = 10;
+            let b = 3;
+            let c = 4;
+            let d = 2;
+            println(\"{}\", a + b * c - d);
+            println(\"{}\", a - b - c);
+            println(\"{}\", a - (b - c));
+            println(\"{}\", a / d / d);
+            println(\"{}\", (a + b) * c);
+            println(\"{}\", a * b % c);
+            println(\"{}\", a > b && b < c || d == 0);
+            println(\"{}\", a + b * c == 22 && d < 5);
+            println(\"{}\", \"n=\" + (a + b));
+        }",
+        "20\n3\n11\n2\n52\n2\ntrue\ntrue\nn=13",
+    );
+}
+
+#[test]
+fn minimal_parentheses() {
+    let java = java(
+        "fn main() {
+            let a = 10;
+            let b = 3;
+            let c = 4;
+            let x = a + b * c;
+            let y = a - (b - c);
+            let z = (a + b) * c;
+        }",
+    );
+
+    assert!(java.contains("x = a + b * c;"));
+    assert!(java.contains("y = a - (b - c);"));
+    assert!(java.contains("z = (a + b) * c;"));
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn sized_arithmetic_and_casts() {
+    assert_output(
+        "fn factorial(n: i64) -> i64 {
+            if n <= 1 {
+                return 1;
+            }
+            return n * factorial(n - 1);
+        }
+        fn halve(x: f32) -> f32 {
+            return x / 2.0;
+        }
+        fn main() {
+            println(\"{}\", factorial(20));
+            let small: i8 = 100;
+            let mut s = small + 20;
+            s += 10;
+            let widened: int = small;
+            let l: i64 = widened;
+            let d: float = l;
+            let f: f32 = 1.5;
+            let g = f * 2.0;
+            println(\"{} {} {} {} {} {}\", s, widened, l, d, g, halve(3));
+            let n: i16 = 300i16;
+            let x = 1000 as i8;
+            let y = 3.9 as i64;
+            let z = true as i8;
+            let w = n as f32;
+            println(\"{} {} {} {} {}\", n, x, y, z, w);
+            let bits: i64 = 1i64 << 40;
+            let mut mask: i8 = 15;
+            mask <<= 2;
+            mask &= 0x3c;
+            println(\"{} {}\", bits, mask);
+        }",
+        "2432902008176640000\n-126 100 100 100.0 3.0 1.5\n300 -24 3 1 300.0\n1099511627776 60",
+    );
+}
+
+#[test]
+fn sized_fields_and_collections() {
+    assert_output(
+        "struct Acc { pub sum: i64 }
+        impl Acc {
+            pub fn add(&mut self, x: i64) {
+                self.sum += x;
+            }
+        }
+        fn main() {
+            let mut totals: i64[] = [];
+            totals.push(3);
+            totals.push(4);
+            let first = totals.get(0).unwrap_or(0);
+            let mut acc = Acc { sum: 0 };
+            acc.add(7);
+            acc.add(8);
+            println(\"{} {} {}\", totals.len(), first, acc.sum);
+        }",
+        "2 3 15",
+    );
+}
+
+#[test]
+fn narrowing_needs_a_cast() {
+    let errors = errors(
+        "fn main() {
+            let l: i64 = 5;
+            let x: int = l;
+            let f: float = 1.5;
+            let g: f32 = f;
+            let c = 'a';
+            let s: i16 = c;
+            let i: int = c;
+        }",
+    );
+
+    assert!(errors.contains("Expected `int` but found `i64`, use `as` to convert"));
+    assert!(errors.contains("Expected `f32` but found `float`, use `as` to convert"));
+    assert!(errors.contains("Expected `i16` but found `char`, use `as` to convert"));
+    assert!(!errors.contains("Expected `int` but found `char`"));
+}
+
+#[test]
+fn literals_take_element_types() {
+    assert_output(
+        "fn fib(n: int, memo: i64[]) -> i64 {
+            if n < 2 {
+                return n as i64;
+            }
+            if memo[n] != 0 {
+                return memo[n];
+            }
+            let r = fib(n - 1, memo) + fib(n - 2, memo);
+            memo[n] = r;
+            return r;
+        }
+        fn main() {
+            let mut memo: i64[] = [0; 50];
+            let a: i64[] = [1, 2];
+            let t: (i64, f32) = (1, 2.0);
+            let o: Option<i64> = Option::Some(5);
+            let r: Result<i8, string> = Result::Ok(3);
+            println(\"{} {} {} {} {}\", fib(49, memo), a[1], t.1, o.unwrap(), r.unwrap());
+        }",
+        "7778742049 2 2.0 5 3",
+    );
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn consts_and_statics() {
+    assert_output(
+        "const MAX_SIZE: int = 5;
+        const MIN: int = 1;
+        const DOUBLE_MAX: int = MAX_SIZE * 2;
+        const BIG: i64 = 1000;
+        const NAME: string = \"jasmine\";
+        static GREETING: string = \"hello\";
+        static mut COUNTER: int = 0;
+        fn bump() {
+            COUNTER += 1;
+        }
+        fn classify(n: int) -> string {
+            return match n {
+                MIN..=MAX_SIZE => \"small\",
+                _ => \"big\",
+            };
+        }
+        fn main() {
+            for i in MIN..MAX_SIZE {
+                bump();
+            }
+            let names = [\"x\"; 3];
+            println(\"{} {} {} {} {}\", COUNTER, DOUBLE_MAX, BIG, NAME, GREETING);
+            println(\"{} {} {}\", names.len(), classify(3), classify(DOUBLE_MAX));
+        }",
+        "4 10 1000 jasmine hello\n3 small big",
+    );
+}
+
+#[test]
+fn repeated_rows_do_not_alias() {
+    assert_output(
+        "const MAX: int = 3;
+        fn main() {
+            let grid = [[0; MAX]; MAX];
+            grid[0][1] = 7;
+            println(\"{} {} {}\", grid[0][1], grid[1][1], grid[2][1]);
+        }",
+        "7 0 0",
+    );
+}
+
+#[test]
+fn const_values_must_be_constant() {
+    let errors = errors(
+        "fn five() -> int { return 5; }
+        const FIVE: int = five();
+        fn main() {}",
+    );
+
+    assert!(errors.contains("The value of `const FIVE` must be a compile-time constant"));
+}
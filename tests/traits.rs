@@ -0,0 +1,143 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn trait_compiles_to_interface() {
+    let java = java(
+        "trait Shape {
+            fn area(&self) -> float;
+        }
+        struct Square { pub side: float }
+        impl Shape for Square {
+            fn area(&self) -> float { return self.side * self.side; }
+        }
+        fn main() {}",
+    );
+
+    assert!(java.contains("interface Shape {"));
+    assert!(java.contains("implements Shape"));
+}
+
+#[test]
+fn trait_objects_and_bounds() {
+    assert_output(
+        "trait Shape {
+            fn area(&self) -> float;
+            fn name(&self) -> string;
+        }
+        struct Rect { pub w: float, pub h: float }
+        impl Shape for Rect {
+            fn area(&self) -> float { return self.w * self.h; }
+            fn name(&self) -> string { return \"rect\"; }
+        }
+        enum Light { On, Off }
+        impl Shape for Light {
+            fn area(self) -> float { return 0.0; }
+            fn name(self) -> string { return \"light\"; }
+        }
+        fn describe<T>(s: T) where T: Shape {
+            println(\"{} has area {}\", s.name(), s.area());
+        }
+        fn main() {
+            let shapes: Shape[] = [Rect { w: 2.0, h: 3.0 }, Light::On];
+            for s: Shape in shapes { describe(s); }
+        }",
+        "rect has area 6.0\nlight has area 0.0",
+    );
+}
+
+#[test]
+fn static_trait_fn_without_body() {
+    let errors = errors(
+        "trait Shape {
+            fn unit() -> float;
+        }
+        fn main() {}",
+    );
+
+    assert!(
+        errors.contains("`fn unit` in trait `Shape` has no `self` argument, so it needs a body")
+    );
+}
+
+#[test]
+fn default_methods_and_overrides() {
+    let source = "trait Describe {
+            fn name(&self) -> string;
+            fn describe(&self) -> string {
+                return format(\"This is {}\", self.name());
+            }
+            fn kind() -> string {
+                return \"describable\";
+            }
+        }
+        struct Dog { pub age: int }
+        impl Describe for Dog {
+            fn name(&self) -> string { return \"dog\"; }
+        }
+        struct Cat { pub age: int }
+        impl Describe for Cat {
+            fn name(&self) -> string { return \"cat\"; }
+            fn describe(&self) -> string { return \"a cat, obviously\"; }
+        }
+        fn main() {
+            let d: Dog = Dog { age: 3 };
+            let c: Cat = Cat { age: 2 };
+            println(\"{}\", d.describe());
+            println(\"{}\", c.describe());
+            println(\"{}\", Describe::kind());
+        }";
+
+    let java = java(source);
+    assert!(java.contains("public default String describe()"));
+    assert!(java.contains("public static String kind()"));
+
+    assert_output(source, "This is dog\na cat, obviously\ndescribable");
+}
+
+#[test]
+fn self_in_trait_signatures() {
+    assert_output(
+        "trait Doubler {
+            fn doubled(&self) -> Self;
+            fn show(&self) -> string;
+        }
+        struct Num { pub n: int }
+        impl Doubler for Num {
+            fn doubled(&self) -> Self { return Num { n: self.n * 2 }; }
+            fn show(&self) -> string { return format(\"{}\", self.n); }
+        }
+        fn quadrupled<T: Doubler>(x: T) -> string {
+            return x.doubled().doubled().show();
+        }
+        fn main() {
+            let n = Num { n: 3 };
+            println(\"{} {}\", n.doubled().n, quadrupled(n));
+        }",
+        "6 12",
+    );
+}
+
+#[test]
+fn trait_impl_errors() {
+    let errors = errors(
+        "trait Shape {
+            fn area(&self) -> float;
+            fn name(&self) -> string;
+            fn merged(&self, other: Self) -> float;
+        }
+        struct Square { pub side: float }
+        impl Shape for Square {
+            fn area(&self) -> float { return self.side; }
+        }
+        impl Missing for Square {}
+        fn main() {}",
+    );
+
+    assert!(errors.contains("`impl Shape for Square` is missing `fn name`"));
+    assert!(errors.contains("Cannot find trait `Missing` in `impl Missing for Square`"));
+    assert!(errors.contains(
+        "`fn merged` in trait `Shape` takes `Self`, which java can not express, take `Shape` instead"
+    ));
+}
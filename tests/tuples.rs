@@ -0,0 +1,46 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn tuple_literals_types_and_fields() {
+    assert_output(
+        "fn min_max(v: int[]) -> (int, int) {
+            let mut lo = v.get(0).unwrap();
+            let mut hi = v.get(0).unwrap();
+            for x in v {
+                if x < lo { lo = x; }
+                if x > hi { hi = x; }
+            }
+            return (lo, hi);
+        }
+        fn main() {
+            let pair = (1, \"a\");
+            println(\"{} {}\", pair.0, pair.1);
+            let mm = min_max([4, 9, 2, 7]);
+            println(\"min {} max {}\", mm.0, mm.1);
+            println(\"{}\", mm);
+            let nested: ((int, char), float) = ((1, 'x'), 2.5);
+            println(\"{}\", nested.0.1);
+            println(\"{}\", pair.equals((1, \"a\")));
+            let pts: (int, int)[] = [(1, 2), (3, 4)];
+            for p in pts { println(\"{}\", p.0 + p.1); }
+        }",
+        "1 a\nmin 2 max 9\n(2, 9)\nx\ntrue\n3\n7",
+    );
+}
+
+#[test]
+fn tuple_classes_are_generated_once() {
+    let java = java(
+        "fn main() {
+            let pair = (1, 2);
+            let triple = (1, 2, 3);
+            let v: int[] = [5, 6];
+            for entry in v.enumerate() { println(\"{} {}\", entry.0, entry.1); }
+        }",
+    );
+
+    assert_eq!(java.matches("class Tuple2<").count(), 1);
+    assert_eq!(java.matches("class Tuple3<").count(), 1);
+}
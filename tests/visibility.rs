@@ -0,0 +1,204 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn public_and_crate_items() {
+    assert_output(
+        "pub struct Account {
+            owner: string,
+            pub(crate) id: int,
+            balance: int
+        }
+        impl Account {
+            const LIMIT: int = 1000;
+            pub const BANK: string = \"JB\";
+
+            pub fn new(owner: string, id: int) -> Account {
+                return Account { owner: owner, id: id, balance: 0 };
+            }
+            pub fn deposit(&mut self, amount: int) {
+                if self.allowed(amount) {
+                    self.balance += amount;
+                }
+            }
+            fn allowed(&self, amount: int) -> bool {
+                return amount <= Self::LIMIT;
+            }
+            pub fn balance(&self) -> int {
+                return self.balance;
+            }
+            pub fn same_owner(&self, other: Account) -> bool {
+                return self.owner == other.owner;
+            }
+        }
+        impl Display for Account {
+            fn fmt(&self) -> string {
+                return format(\"{}#{}: {}\", self.owner, self.id, self.balance);
+            }
+        }
+        pub(crate) fn helper() -> int {
+            return 2;
+        }
+        fn main() {
+            let mut a = Account::new(\"ana\", 1);
+            a.deposit(500);
+            a.deposit(5000);
+            println(\"{} {} {} {}\", a, a.balance(), a.id, Account::BANK);
+            println(\"{}\", a.same_owner(Account::new(\"ana\", 2)));
+            println(\"{}\", helper());
+        }",
+        "ana#1: 500 500 1 JB
+true
+2",
+    );
+}
+
+#[test]
+fn java_modifiers() {
+    let java = java(
+        "pub struct A {
+            x: int,
+            pub(crate) y: int,
+            pub z: int
+        }
+        impl A {
+            fn hidden(&self) -> int {
+                return self.x;
+            }
+            pub fn shown(&self) -> int {
+                return self.hidden();
+            }
+        }
+        fn main() {}",
+    );
+
+    assert!(java.contains("private Integer x;"));
+    assert!(java.contains(" Integer y;"));
+    assert!(!java.contains("private Integer y;"));
+    assert!(java.contains("public Integer z;"));
+    assert!(java.contains("private Integer hidden()"));
+    assert!(java.contains("public Integer shown()"));
+}
+
+#[test]
+fn private_items_in_their_module() {
+    assert_output(
+        "struct Point {
+            x: int,
+            pub y: int
+        }
+        impl Point {
+            fn new(x: int) -> Point {
+                return Point { x: x, y: 0 };
+            }
+        }
+        fn main() {
+            let p = Point::new(3);
+            println(\"{} {}\", p.x, p.y);
+        }",
+        "3 0",
+    );
+}
+
+#[test]
+fn private_items_outside_their_module() {
+    let errors = errors_files(&[
+        (
+            "main.jasmine",
+            "mod geometry;
+            use geometry::Point;
+            struct Other {
+                pub p: Point
+            }
+            impl Other {
+                pub fn peek(&self) -> int {
+                    return self.p.x + self.p.secret();
+                }
+            }
+            fn main() {
+                let mut p = Point::make();
+                p.y = 4;
+                let f = Point::secret;
+                println(\"{} {}\", p.y, Point::ORIGIN);
+            }",
+        ),
+        (
+            "geometry/mod.jasmine",
+            "mod shapes;
+            pub struct Point {
+                x: int,
+                pub y: int
+            }
+            impl Point {
+                const ORIGIN: int = 0;
+
+                fn secret(&self) -> int {
+                    return self.x;
+                }
+                fn make() -> Point {
+                    return Point { x: 0, y: 0 };
+                }
+            }
+            pub fn origin_x(p: Point) -> int {
+                return p.x;
+            }",
+        ),
+        (
+            "geometry/shapes.jasmine",
+            "use super::Point;
+            pub fn secret_of(p: Point) -> int {
+                return p.secret();
+            }",
+        ),
+    ]);
+
+    assert!(errors.contains(
+        "Field `x` of `Point` is private to `geometry`, mark it `pub` to use it in `crate`"
+    ));
+    assert!(errors.contains(
+        "Method `secret` of `Point` is private to `geometry`, mark it `pub` to use it in `crate`"
+    ));
+    assert!(errors.contains(
+        "Method `make` of `Point` is private to `geometry`, mark it `pub` to use it in `crate`"
+    ));
+    assert!(errors.contains(
+        "Constant `ORIGIN` of `Point` is private to `geometry`, mark it `pub` to use it in `crate`"
+    ));
+    assert!(!errors.contains("Field `y`"));
+    assert!(!errors.contains("in `geometry`"));
+    assert!(!errors.contains("in `geometry::shapes`"));
+}
+
+#[test]
+fn static_visibility() {
+    let java = java(
+        "static A: int = 1;
+        pub static B: int = 2;
+        pub(crate) static mut C: int = 3;
+        fn main() {}",
+    );
+
+    assert!(java.contains("private static final Integer A = 1;"));
+    assert!(java.contains("public static final Integer B = 2;"));
+    assert!(java.contains(" static Integer C = 3;"));
+    assert!(!java.contains("public static Integer C"));
+    assert!(!java.contains("private static Integer C"));
+}
+
+#[test]
+fn private_statics_are_not_importable() {
+    let errors = errors_files(&[
+        (
+            "main.jasmine",
+            "mod config;
+            use config::LIMIT;
+            fn main() {}",
+        ),
+        ("config.jasmine", "static LIMIT: int = 3;"),
+    ]);
+
+    assert!(
+        errors.contains("`LIMIT` is private to `config`, mark it `pub` to import it in `crate`")
+    );
+}